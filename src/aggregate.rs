@@ -0,0 +1,149 @@
+use crate::{Pipe, ResetablePipe};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// A pipe that aggregates values per key.
+///
+/// The input items are key-value pairs. Every key has an accumulator, which starts as a clone of the initial accumulator, and every value is folded into the accumulator of its key using the fold function. The output item is a clone of the updated accumulator, which makes running counts or sums available downstream.
+///
+/// The groups are kept until they are emitted and removed with [`drain`](#method.drain), for example at the end of a window. Resetting this pipe removes all groups too.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipe = AggregateByKey::new(0, |count: &mut u32, _: ()| *count += 1);
+/// assert_eq!(1, pipe.next(('a', ())));
+/// assert_eq!(1, pipe.next(('b', ())));
+/// assert_eq!(2, pipe.next(('a', ())));
+///
+/// let mut groups = pipe.drain();
+/// groups.sort();
+/// assert_eq!(vec![('a', 2), ('b', 1)], groups);
+/// assert!(pipe.is_empty());
+/// ```
+pub struct AggregateByKey<K, V, A, F>
+where
+    K: Eq + Hash,
+    A: Clone,
+    F: FnMut(&mut A, V),
+{
+    groups: HashMap<K, A>,
+    initial: A,
+    fold: F,
+    value: PhantomData<V>,
+}
+
+impl<K, V, A, F> AggregateByKey<K, V, A, F>
+where
+    K: Eq + Hash,
+    A: Clone,
+    F: FnMut(&mut A, V),
+{
+    /// Create a new aggregating pipe with the initial accumulator and the fold function.
+    pub fn new(initial: A, fold: F) -> Self {
+        Self {
+            groups: HashMap::new(),
+            initial,
+            fold,
+            value: PhantomData,
+        }
+    }
+
+    /// Return the accumulator of the given key, if the key was seen since the last drain.
+    pub fn get(&self, key: &K) -> Option<&A> {
+        self.groups.get(key)
+    }
+
+    /// Return the number of groups.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Return `true` if there are no groups.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Remove all groups and return them with their keys.
+    ///
+    /// The groups are returned in an arbitrary order.
+    pub fn drain(&mut self) -> Vec<(K, A)> {
+        self.groups.drain().collect()
+    }
+}
+
+impl<K, V, A, F> Pipe for AggregateByKey<K, V, A, F>
+where
+    K: Eq + Hash,
+    A: Clone,
+    F: FnMut(&mut A, V),
+{
+    type InputItem = (K, V);
+    type OutputItem = A;
+
+    fn next(&mut self, (key, value): (K, V)) -> A {
+        let initial = &self.initial;
+        let accumulator = self.groups.entry(key).or_insert_with(|| initial.clone());
+        (self.fold)(accumulator, value);
+        accumulator.clone()
+    }
+}
+
+impl<K, V, A, F> ResetablePipe for AggregateByKey<K, V, A, F>
+where
+    K: Eq + Hash,
+    A: Clone,
+    F: FnMut(&mut A, V),
+{
+    fn reset(&mut self) {
+        self.groups.clear();
+    }
+}
+
+#[test]
+fn aggregate_by_key() {
+    use crate::dsp::XorShift;
+
+    let mut rng = XorShift::new(42);
+    let mut events: Vec<(u8, u32)> = (0..512).map(|i| ((rng.next_u64() % 8) as u8, i)).collect();
+    // Shuffle the events.
+    for i in (1..events.len()).rev() {
+        let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+        events.swap(i, j);
+    }
+
+    // Count, sum and remember the last value per key.
+    let mut pipe = AggregateByKey::new(
+        (0usize, 0u32, None),
+        |acc: &mut (usize, u32, Option<u32>), value: u32| {
+            acc.0 += 1;
+            acc.1 += value;
+            acc.2 = Some(value);
+        },
+    );
+
+    for _ in 0..2 {
+        let mut expected: HashMap<u8, (usize, u32, Option<u32>)> = HashMap::new();
+        for &(key, value) in events.iter() {
+            let acc = expected.entry(key).or_insert((0, 0, None));
+            acc.0 += 1;
+            acc.1 += value;
+            acc.2 = Some(value);
+            assert_eq!(*acc, pipe.next((key, value)));
+        }
+        assert_eq!(expected.len(), pipe.len());
+        assert_eq!(expected.get(&3), pipe.get(&3));
+
+        let groups: HashMap<u8, (usize, u32, Option<u32>)> = pipe.drain().into_iter().collect();
+        assert_eq!(expected, groups);
+        assert!(pipe.drain().is_empty());
+    }
+
+    pipe.next((1, 1));
+    pipe.reset();
+    assert!(pipe.is_empty());
+    assert_eq!((1, 2, Some(2)), pipe.next((1, 2)));
+}
@@ -0,0 +1,524 @@
+use crate::{ConsumeResult, Pipe, ResetablePipe};
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::vec;
+
+/// A consumer that keeps the latest items in a ring buffer.
+///
+/// This consumer owns a buffer with a fixed capacity. Input items are appended to the buffer and once it's full, the oldest item is overwritten. Therefore, it never reports `ConsumeResult::Full` and always contains the latest items, which is useful for scope or metering displays. Writing an item never allocates memory.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut consumer: RingConsumer<u8> = RingConsumer::new(3);
+/// for i in 0..5 {
+///     assert_eq!(ConsumeResult::Consumed, consumer.next(i));
+/// }
+///
+/// assert_eq!((&[2][..], &[3, 4][..]), consumer.as_ordered());
+/// assert_eq!(vec![3, 4], consumer.latest(2).cloned().collect::<Vec<u8>>());
+/// ```
+pub struct RingConsumer<T> {
+    data: Vec<T>,
+    capacity: usize,
+    head: usize,
+}
+
+impl<T> RingConsumer<T> {
+    /// Create a new ring consumer with the given capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity != 0, "The capacity must not be zero");
+        Self {
+            data: Vec::with_capacity(capacity),
+            capacity,
+            head: 0,
+        }
+    }
+
+    /// Return the capacity of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Return the number of items in the buffer.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Return `true` if the buffer contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Return the contents of the buffer in chronological order.
+    ///
+    /// Since the buffer wraps around, the contents are split into two contiguous slices. The first slice contains the oldest items and the second slice contains the newer ones.
+    pub fn as_ordered(&self) -> (&[T], &[T]) {
+        let (newer, older) = self.data.split_at(self.head);
+        (older, newer)
+    }
+
+    /// Iterate over the latest `n` items in chronological order.
+    ///
+    /// If the buffer contains less than `n` items, all items are returned.
+    pub fn latest(&self, n: usize) -> impl Iterator<Item = &T> {
+        let (first, second) = self.as_ordered();
+        let skipped = self.data.len() - usize::min(n, self.data.len());
+        first.iter().chain(second.iter()).skip(skipped)
+    }
+}
+
+impl<T> Pipe for RingConsumer<T> {
+    type InputItem = T;
+    type OutputItem = ConsumeResult;
+
+    fn next(&mut self, item: T) -> ConsumeResult {
+        if self.data.len() < self.capacity {
+            self.data.push(item);
+        } else {
+            self.data[self.head] = item;
+            self.head = (self.head + 1) % self.capacity;
+        }
+        ConsumeResult::Consumed
+    }
+}
+
+impl<T> ResetablePipe for RingConsumer<T> {
+    fn reset(&mut self) {
+        self.data.clear();
+        self.head = 0;
+    }
+}
+
+/// A first-in, first-out queue that decouples pushing items from popping them.
+///
+/// The buffer can be driven manually with [`push`](#method.push) and [`pop`](#method.pop), for example between the runs of a pipeline, or used as a pipe: The input item is an `Option<T>` that is pushed if it's `Some`, and the output item is the oldest item of the buffer, or `None` if the buffer is empty. This way, items that were pushed in a burst can be drained over several calls.
+///
+/// A buffer may have a capacity. Then, [`push`](#method.push) rejects items once the buffer is full and returns them. As a pipe, the buffer never rejects items since it pops an item for every item it pushes. Resetting the buffer removes all items. When the buffer is finished, the remaining items are drained.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut buffer: Buffer<u8> = Buffer::bounded(2);
+/// assert_eq!(Ok(()), buffer.push(1));
+/// assert_eq!(Ok(()), buffer.push(2));
+/// assert_eq!(Err(3), buffer.push(3));
+///
+/// assert_eq!(Some(1), buffer.next(Some(4)));
+/// assert_eq!(Some(2), buffer.next(None));
+/// assert_eq!(Some(4), buffer.next(None));
+/// assert_eq!(None, buffer.next(None));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Buffer<T> {
+    items: VecDeque<T>,
+    capacity: Option<usize>,
+}
+
+impl<T> Buffer<T> {
+    /// Create a new buffer without a capacity.
+    pub fn new() -> Self {
+        Self {
+            items: VecDeque::new(),
+            capacity: None,
+        }
+    }
+
+    /// Create a new buffer that holds at most `capacity` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn bounded(capacity: usize) -> Self {
+        assert!(capacity != 0, "The capacity must not be zero");
+        Self {
+            items: VecDeque::with_capacity(capacity),
+            capacity: Some(capacity),
+        }
+    }
+
+    /// Return the capacity of the buffer, or `None` if it's unbounded.
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// Return the number of items in the buffer.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Return `true` if the buffer contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Return `true` if the buffer can't accept any more items.
+    pub fn is_full(&self) -> bool {
+        self.capacity
+            .is_some_and(|capacity| self.items.len() >= capacity)
+    }
+
+    /// Append an item to the buffer.
+    ///
+    /// If the buffer is full, the item is rejected and returned.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            Err(item)
+        } else {
+            self.items.push_back(item);
+            Ok(())
+        }
+    }
+
+    /// Remove the oldest item from the buffer and return it.
+    pub fn pop(&mut self) -> Option<T> {
+        self.items.pop_front()
+    }
+
+    /// Return a reference to the oldest item of the buffer.
+    pub fn front(&self) -> Option<&T> {
+        self.items.front()
+    }
+}
+
+impl<T> Default for Buffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Pipe for Buffer<T> {
+    type InputItem = Option<T>;
+    type OutputItem = Option<T>;
+
+    fn next(&mut self, item: Option<T>) -> Option<T> {
+        // Pushing before popping lets items pass an empty buffer immediately. A full buffer pops first, so the capacity is never exceeded.
+        if self.is_full() {
+            let oldest = self.items.pop_front();
+            self.items.extend(item);
+            oldest
+        } else {
+            self.items.extend(item);
+            self.items.pop_front()
+        }
+    }
+
+    fn finish(&mut self) -> Vec<Option<T>> {
+        self.items.drain(..).map(Some).collect()
+    }
+}
+
+impl<T> ResetablePipe for Buffer<T> {
+    fn reset(&mut self) {
+        self.items.clear();
+    }
+}
+
+/// A pipe that collects items into blocks, sorts them and emits them in order.
+///
+/// The input item is an `Option<T>` and `Some` items are collected into a block. The block is flushed if a `None` input item signals the end of the stream, or if the block reaches the capacity that may be set with [`bounded`](#method.bounded). Flushing sorts the block and then, one sorted item is returned per call until the block is drained, starting with the call that triggered the flush. While items are collected, `None` is returned.
+///
+/// Input items that arrive while a block is drained are collected into the next block, which is flushed once the current block is drained and it's either full or the input item is `None`. Therefore, the end of a stream should be signalled with `None` input items until `None` is returned.
+///
+/// The items are sorted with a stable sort, either by their natural order or by a comparison function. Resetting this pipe discards all items. When this pipe is finished, the remaining items of the drained block are returned, followed by the sorted items of the next block.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipe = SortBuffer::new();
+/// assert_eq!(None, pipe.next(Some(3)));
+/// assert_eq!(None, pipe.next(Some(1)));
+/// assert_eq!(None, pipe.next(Some(2)));
+/// assert_eq!(Some(1), pipe.next(None));
+/// assert_eq!(Some(2), pipe.next(None));
+/// assert_eq!(Some(3), pipe.next(None));
+/// assert_eq!(None, pipe.next(None));
+/// ```
+pub struct SortBuffer<T, F = fn(&T, &T) -> Ordering>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    incoming: Vec<T>,
+    outgoing: vec::IntoIter<T>,
+    capacity: Option<usize>,
+    compare: F,
+}
+
+impl<T: Ord> SortBuffer<T> {
+    /// Create a new sorting buffer that sorts its items by their natural order.
+    pub fn new() -> Self {
+        Self::sort_by(T::cmp)
+    }
+}
+
+impl<T: Ord> Default for SortBuffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SortBuffer<T> {
+    /// Create a new sorting buffer that sorts its items by a key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = SortBuffer::sort_by_key(|item: &(u8, char)| item.0).bounded(2);
+    /// assert_eq!(None, pipe.next(Some((2, 'a'))));
+    /// assert_eq!(Some((1, 'b')), pipe.next(Some((1, 'b'))));
+    /// assert_eq!(Some((2, 'a')), pipe.next(None));
+    /// ```
+    pub fn sort_by_key<K, G>(mut key: G) -> SortBuffer<T, impl FnMut(&T, &T) -> Ordering>
+    where
+        K: Ord,
+        G: FnMut(&T) -> K,
+    {
+        SortBuffer::sort_by(move |a: &T, b: &T| key(a).cmp(&key(b)))
+    }
+}
+
+impl<T, F> SortBuffer<T, F>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    /// Create a new sorting buffer that sorts its items with a comparison function.
+    pub fn sort_by(compare: F) -> Self {
+        Self {
+            incoming: Vec::new(),
+            outgoing: Vec::new().into_iter(),
+            capacity: None,
+            compare,
+        }
+    }
+
+    /// Flush a block as soon as it contains `capacity` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn bounded(mut self, capacity: usize) -> Self {
+        assert!(capacity != 0, "The capacity must not be zero");
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Return the number of collected items that aren't flushed yet.
+    pub fn collected(&self) -> usize {
+        self.incoming.len()
+    }
+
+    /// Return the number of flushed items that aren't emitted yet.
+    pub fn pending(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    fn flush(&mut self) {
+        let mut block = std::mem::take(&mut self.incoming);
+        block.sort_by(&mut self.compare);
+        self.outgoing = block.into_iter();
+    }
+}
+
+impl<T, F> Pipe for SortBuffer<T, F>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    type InputItem = Option<T>;
+    type OutputItem = Option<T>;
+
+    fn next(&mut self, item: Option<T>) -> Option<T> {
+        let end_of_stream = item.is_none();
+        if let Some(item) = item {
+            self.incoming.push(item);
+        }
+        let full = self
+            .capacity
+            .is_some_and(|capacity| self.incoming.len() >= capacity);
+        if self.outgoing.len() == 0 && !self.incoming.is_empty() && (end_of_stream || full) {
+            self.flush();
+        }
+        self.outgoing.next()
+    }
+
+    fn finish(&mut self) -> Vec<Option<T>> {
+        let mut items: Vec<Option<T>> = self.outgoing.by_ref().map(Some).collect();
+        self.flush();
+        items.extend(self.outgoing.by_ref().map(Some));
+        items
+    }
+}
+
+impl<T, F> ResetablePipe for SortBuffer<T, F>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    fn reset(&mut self) {
+        self.incoming.clear();
+        self.outgoing = Vec::new().into_iter();
+    }
+}
+
+#[test]
+fn ring_consumer_wrap() {
+    let mut consumer: RingConsumer<usize> = RingConsumer::new(4);
+    assert!(consumer.is_empty());
+
+    for i in 0..3 {
+        consumer.next(i);
+    }
+    assert_eq!((&[0, 1, 2][..], &[][..]), consumer.as_ordered());
+
+    for i in 3..=17 {
+        assert_eq!(ConsumeResult::Consumed, consumer.next(i));
+
+        let (first, second) = consumer.as_ordered();
+        let ordered: Vec<usize> = first.iter().chain(second.iter()).cloned().collect();
+        assert_eq!(ordered, (i - 3..=i).collect::<Vec<usize>>());
+    }
+
+    assert_eq!(4, consumer.len());
+    assert_eq!(
+        vec![15, 16, 17],
+        consumer.latest(3).cloned().collect::<Vec<usize>>()
+    );
+    assert_eq!(
+        vec![14, 15, 16, 17],
+        consumer.latest(10).cloned().collect::<Vec<usize>>()
+    );
+}
+
+#[test]
+fn ring_consumer_reset() {
+    let mut consumer: RingConsumer<usize> = RingConsumer::new(2);
+    for i in 0..5 {
+        consumer.next(i);
+    }
+    consumer.reset();
+    assert!(consumer.is_empty());
+    assert_eq!(0, consumer.latest(2).count());
+
+    consumer.next(42);
+    assert_eq!((&[42][..], &[][..]), consumer.as_ordered());
+}
+
+#[test]
+fn buffer_burst() {
+    let mut buffer: Buffer<u32> = Buffer::new();
+    assert_eq!(None, buffer.capacity());
+
+    // Push bursts manually and drain them with the pipe.
+    for burst in 0..4 {
+        for i in 0..10 {
+            buffer.push(burst * 10 + i).unwrap();
+        }
+        assert_eq!(10, buffer.len());
+        assert_eq!(Some(&(burst * 10)), buffer.front());
+
+        let drained: Vec<u32> = (0..12).filter_map(|_| buffer.next(None)).collect();
+        assert_eq!((burst * 10..burst * 10 + 10).collect::<Vec<u32>>(), drained);
+        assert!(buffer.is_empty());
+    }
+
+    // The pipe keeps the order of manually pushed items.
+    buffer.push(1).unwrap();
+    buffer.push(2).unwrap();
+    assert_eq!(Some(1), buffer.next(Some(3)));
+    assert_eq!(vec![Some(2), Some(3)], buffer.finish());
+    assert_eq!(Some(4), buffer.next(Some(4)));
+
+    buffer.push(5).unwrap();
+    buffer.reset();
+    assert_eq!(None, buffer.pop());
+}
+
+#[test]
+fn buffer_bounded() {
+    let mut buffer: Buffer<char> = Buffer::bounded(3);
+    assert_eq!(Some(3), buffer.capacity());
+
+    for c in "abc".chars() {
+        assert_eq!(Ok(()), buffer.push(c));
+    }
+    assert!(buffer.is_full());
+    assert_eq!(Err('d'), buffer.push('d'));
+    assert_eq!(3, buffer.len());
+
+    // The pipe never rejects items.
+    assert_eq!(Some('a'), buffer.next(Some('e')));
+    assert_eq!(3, buffer.len());
+
+    assert_eq!(Some('b'), buffer.pop());
+    assert_eq!(Ok(()), buffer.push('f'));
+    let remaining: Vec<char> = std::iter::from_fn(|| buffer.pop()).collect();
+    assert_eq!(vec!['c', 'e', 'f'], remaining);
+}
+
+#[test]
+fn sort_buffer_random() {
+    use crate::dsp::XorShift;
+
+    let mut rng = XorShift::new(7);
+    let mut pipe = SortBuffer::new();
+    for len in 0..32 {
+        let data: Vec<u64> = (0..len).map(|_| rng.next_u64() % 100).collect();
+        for item in data.iter() {
+            assert_eq!(None, pipe.next(Some(*item)));
+        }
+        assert_eq!(len, pipe.collected());
+
+        let sorted: Vec<u64> = std::iter::from_fn(|| pipe.next(None)).collect();
+        let mut expected = data.clone();
+        expected.sort_unstable();
+        assert_eq!(expected, sorted);
+    }
+
+    pipe.next(Some(1));
+    pipe.reset();
+    assert_eq!(None, pipe.next(None));
+}
+
+#[test]
+fn sort_buffer_bounded() {
+    let mut pipe = SortBuffer::new().bounded(3);
+    let input = [5, 4, 3, 9, 8, 7, 6];
+    let output: Vec<Option<u8>> = input.iter().map(|i| pipe.next(Some(*i))).collect();
+    // The first block is flushed by the capacity while the second block is collected.
+    assert_eq!(
+        vec![None, None, Some(3), Some(4), Some(5), Some(7), Some(8)],
+        output
+    );
+    assert_eq!(1, pipe.pending());
+    assert_eq!(1, pipe.collected());
+
+    assert_eq!(Some(9), pipe.next(None));
+    assert_eq!(Some(6), pipe.next(None));
+    assert_eq!(None, pipe.next(None));
+
+    // Finishing returns the drained block, followed by the sorted next block.
+    let output: Vec<Option<u8>> = [3, 2, 1, 6].iter().map(|i| pipe.next(Some(*i))).collect();
+    assert_eq!(vec![None, None, Some(1), Some(2)], output);
+    assert_eq!(vec![Some(3), Some(6)], pipe.finish());
+    assert_eq!(None, pipe.next(None));
+}
+
+#[test]
+fn sort_buffer_by_key_is_stable() {
+    let mut pipe = SortBuffer::sort_by_key(|item: &(u8, char)| item.0);
+    for item in [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')] {
+        pipe.next(Some(item));
+    }
+    let sorted: Vec<(u8, char)> = std::iter::from_fn(|| pipe.next(None)).collect();
+    assert_eq!(vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')], sorted);
+}
@@ -0,0 +1,342 @@
+use crate::{Pipe, ResetablePipe};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// A source of the current time.
+///
+/// Time-based pipes like [`Throttle`](struct.Throttle.html) retrieve the current time from a clock. By default, this is the [`SystemClock`](struct.SystemClock.html), but the [`ManualClock`](struct.ManualClock.html) may be used to make tests deterministic.
+pub trait Clock {
+    /// Return the current time.
+    fn now(&self) -> Instant;
+}
+
+/// A clock that returns the current system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only advances when it's told to.
+///
+/// Clones of a manual clock share their time, so a clone may be moved into a pipe while the original is used to advance the time.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+/// use std::time::Duration;
+///
+/// let clock = ManualClock::new();
+/// let start = clock.now();
+///
+/// let shared = clock.clone();
+/// clock.advance(Duration::from_secs(1));
+/// assert_eq!(Duration::from_secs(1), shared.now() - start);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    start: Instant,
+    elapsed: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    /// Create a new manual clock.
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Rc::new(Cell::new(Duration::from_secs(0))),
+        }
+    }
+
+    /// Advance the time of the clock and all of its clones.
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed.set(self.elapsed.get() + duration);
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed.get()
+    }
+}
+
+/// A pipe that limits the rate of another pipe's output items.
+///
+/// The wrapped pipe processes every input item, but its output item is only passed through as `Some(item)` if at least `interval` has elapsed since the last passed item. Otherwise, `None` is returned. If `leading` is `true`, the first item passes immediately. If it's `false`, the first item only starts the interval and is dropped.
+///
+/// Resetting this pipe resets the wrapped pipe and forgets the time of the last passed item.
+///
+/// For more information, please see [the documentation of the `throttle` method](trait.Pipe.html#method.throttle).
+pub struct Throttle<P, C = SystemClock>
+where
+    P: Pipe,
+    C: Clock,
+{
+    pipe: P,
+    interval: Duration,
+    leading: bool,
+    clock: C,
+    last_emit: Option<Instant>,
+}
+
+impl<P: Pipe> Throttle<P, SystemClock> {
+    /// Create a new throttling pipe that uses the system clock and passes the first item immediately.
+    pub fn new(pipe: P, interval: Duration) -> Self {
+        Self::with_clock(pipe, interval, true, SystemClock)
+    }
+}
+
+impl<P: Pipe, C: Clock> Throttle<P, C> {
+    /// Create a new throttling pipe with a custom clock.
+    pub fn with_clock(pipe: P, interval: Duration, leading: bool, clock: C) -> Self {
+        Self {
+            pipe,
+            interval,
+            leading,
+            clock,
+            last_emit: None,
+        }
+    }
+
+    /// Pass the item if the interval has elapsed since the last passed item.
+    fn throttle(&mut self, item: P::OutputItem) -> Option<P::OutputItem> {
+        let now = self.clock.now();
+        let pass = match self.last_emit {
+            Some(last_emit) => now.duration_since(last_emit) >= self.interval,
+            None if self.leading => true,
+            None => {
+                self.last_emit = Some(now);
+                false
+            }
+        };
+        if pass {
+            self.last_emit = Some(now);
+            Some(item)
+        } else {
+            None
+        }
+    }
+}
+
+impl<P: Pipe, C: Clock> Pipe for Throttle<P, C> {
+    type InputItem = P::InputItem;
+    type OutputItem = Option<P::OutputItem>;
+
+    fn next(&mut self, item: P::InputItem) -> Option<P::OutputItem> {
+        let item = self.pipe.next(item);
+        self.throttle(item)
+    }
+
+    fn finish(&mut self) -> Vec<Option<P::OutputItem>> {
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(|item| self.throttle(item))
+            .collect()
+    }
+}
+
+impl<P: ResetablePipe, C: Clock> ResetablePipe for Throttle<P, C> {
+    fn reset(&mut self) {
+        self.pipe.reset();
+        self.last_emit = None;
+    }
+}
+
+/// A pipe that annotates the output items of another pipe with the time they were produced.
+///
+/// For more information, please see [the documentation of the `timestamp` method](trait.Pipe.html#method.timestamp).
+pub struct Timestamp<P, C = SystemClock>
+where
+    P: Pipe,
+    C: Clock,
+{
+    pipe: P,
+    clock: C,
+}
+
+impl<P: Pipe> Timestamp<P, SystemClock> {
+    /// Create a new timestamping pipe that uses the system clock.
+    pub fn new(pipe: P) -> Self {
+        Self::with_clock(pipe, SystemClock)
+    }
+}
+
+impl<P: Pipe, C: Clock> Timestamp<P, C> {
+    /// Create a new timestamping pipe with a custom clock.
+    pub fn with_clock(pipe: P, clock: C) -> Self {
+        Self { pipe, clock }
+    }
+}
+
+impl<P: Pipe, C: Clock> Pipe for Timestamp<P, C> {
+    type InputItem = P::InputItem;
+    type OutputItem = (Instant, P::OutputItem);
+
+    fn next(&mut self, item: P::InputItem) -> (Instant, P::OutputItem) {
+        let item = self.pipe.next(item);
+        (self.clock.now(), item)
+    }
+
+    fn finish(&mut self) -> Vec<(Instant, P::OutputItem)> {
+        let clock = &self.clock;
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(|item| (clock.now(), item))
+            .collect()
+    }
+}
+
+impl<P: ResetablePipe, C: Clock> ResetablePipe for Timestamp<P, C> {
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A pipe that annotates the output items of another pipe with their sample position.
+///
+/// The sample position starts with a configurable value and is incremented with every call. Using the sample rate, a sample position can be converted to seconds with [`seconds`](#method.seconds). Resetting this pipe resets the wrapped pipe and restores the starting position.
+///
+/// For more information, please see [the documentation of the `sample_stamp` method](trait.Pipe.html#method.sample_stamp).
+pub struct SampleStamp<P>
+where
+    P: Pipe,
+{
+    pipe: P,
+    start: u64,
+    sample_rate: f64,
+    position: u64,
+}
+
+impl<P: Pipe> SampleStamp<P> {
+    /// Create a new sample stamping pipe.
+    pub fn new(pipe: P, start: u64, sample_rate: f64) -> Self {
+        Self {
+            pipe,
+            start,
+            sample_rate,
+            position: start,
+        }
+    }
+
+    /// Convert a sample position into seconds.
+    pub fn seconds(&self, stamp: u64) -> f64 {
+        stamp as f64 / self.sample_rate
+    }
+}
+
+impl<P: Pipe> Pipe for SampleStamp<P> {
+    type InputItem = P::InputItem;
+    type OutputItem = (u64, P::OutputItem);
+
+    fn next(&mut self, item: P::InputItem) -> (u64, P::OutputItem) {
+        let stamp = self.position;
+        self.position += 1;
+        (stamp, self.pipe.next(item))
+    }
+
+    fn finish(&mut self) -> Vec<(u64, P::OutputItem)> {
+        let items = self.pipe.finish();
+        let start = self.position;
+        self.position += items.len() as u64;
+        (start..).zip(items).collect()
+    }
+}
+
+impl<P: ResetablePipe> ResetablePipe for SampleStamp<P> {
+    fn reset(&mut self) {
+        self.pipe.reset();
+        self.position = self.start;
+    }
+}
+
+#[test]
+fn throttle_leading() {
+    use crate::Counter;
+
+    let clock = ManualClock::new();
+    let mut pipe = Throttle::with_clock(
+        Counter::new(0u32, 1),
+        Duration::from_millis(10),
+        true,
+        clock.clone(),
+    );
+
+    assert_eq!(Some(0), pipe.next(()));
+    clock.advance(Duration::from_millis(5));
+    assert_eq!(None, pipe.next(()));
+    clock.advance(Duration::from_millis(5));
+    assert_eq!(Some(2), pipe.next(()));
+    assert_eq!(None, pipe.next(()));
+    clock.advance(Duration::from_millis(25));
+    assert_eq!(Some(4), pipe.next(()));
+
+    pipe.reset();
+    assert_eq!(Some(0), pipe.next(()));
+}
+
+#[test]
+fn throttle_trailing() {
+    use crate::Counter;
+
+    let clock = ManualClock::new();
+    let mut pipe = Throttle::with_clock(
+        Counter::new(0u32, 1),
+        Duration::from_millis(10),
+        false,
+        clock.clone(),
+    );
+
+    assert_eq!(None, pipe.next(()));
+    clock.advance(Duration::from_millis(9));
+    assert_eq!(None, pipe.next(()));
+    clock.advance(Duration::from_millis(1));
+    assert_eq!(Some(2), pipe.next(()));
+
+    pipe.reset();
+    assert_eq!(None, pipe.next(()));
+    clock.advance(Duration::from_millis(10));
+    assert_eq!(Some(1), pipe.next(()));
+}
+
+#[test]
+fn timestamp() {
+    use crate::Ditto;
+
+    let clock = ManualClock::new();
+    let start = clock.now();
+    let mut pipe = Timestamp::with_clock(Ditto::<char>::default(), clock.clone());
+
+    assert_eq!((start, 'a'), pipe.next('a'));
+    clock.advance(Duration::from_millis(3));
+    assert_eq!((start + Duration::from_millis(3), 'b'), pipe.next('b'));
+}
+
+#[test]
+fn sample_stamp() {
+    use crate::Counter;
+
+    let mut pipe = Counter::new(0u8, 1).sample_stamp(100, 48_000.0);
+    assert_eq!((100, 0), pipe.next(()));
+    assert_eq!((101, 1), pipe.next(()));
+    assert_eq!((102, 2), pipe.next(()));
+
+    pipe.reset();
+    let stamps: Vec<(u64, u8)> = (0..4).map(|_| pipe.next(())).collect();
+    assert_eq!(vec![(100, 0), (101, 1), (102, 2), (103, 3)], stamps);
+
+    assert_eq!(1.0, pipe.seconds(48_000));
+    assert_eq!(0.5, pipe.seconds(24_000));
+}
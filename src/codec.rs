@@ -0,0 +1,618 @@
+use crate::{Pipe, ResetablePipe};
+use std::collections::VecDeque;
+use std::fmt;
+
+/// The alphabet of the standard Base64 encoding, as defined by RFC 4648.
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A pipe that encodes a stream of bytes with the standard Base64 encoding.
+///
+/// The input items are the bytes of the stream, followed by a `None` that signals the end of the stream. Every group of three bytes is encoded into four characters, and the last, incomplete group is padded with `=` once the end of the stream is signalled. Since more characters are produced than bytes are consumed, the characters are queued and one character is returned per call. Therefore, the end of the stream should be signalled with `None` input items until `None` is returned. After that, a new stream may be encoded.
+///
+/// Resetting this pipe drops the partial group and all queued characters. When this pipe is finished, the end of the stream is implied: A partial group is padded and all queued characters are returned.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut encoder = Base64Encode::new();
+/// let mut encoded = String::new();
+/// for byte in b"foob" {
+///     encoded.extend(encoder.next(Some(*byte)));
+/// }
+/// while let Some(c) = encoder.next(None) {
+///     encoded.push(c);
+/// }
+///
+/// assert_eq!("Zm9vYg==", encoded);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Base64Encode {
+    group: u32,
+    len: usize,
+    queue: VecDeque<char>,
+}
+
+impl Base64Encode {
+    /// Create a new encoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue the first `chars` characters of the current group and pad them to four characters.
+    fn emit(&mut self, chars: usize) {
+        for i in 0..4 {
+            if i < chars {
+                let index = (self.group >> (18 - 6 * i)) & 0x3f;
+                self.queue
+                    .push_back(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                self.queue.push_back('=');
+            }
+        }
+        self.group = 0;
+        self.len = 0;
+    }
+}
+
+impl Pipe for Base64Encode {
+    type InputItem = Option<u8>;
+    type OutputItem = Option<char>;
+
+    fn next(&mut self, byte: Option<u8>) -> Option<char> {
+        match byte {
+            Some(byte) => {
+                self.group |= (byte as u32) << (16 - 8 * self.len);
+                self.len += 1;
+                if self.len == 3 {
+                    self.emit(4);
+                }
+            }
+            None if self.len > 0 => self.emit(self.len + 1),
+            None => (),
+        }
+        self.queue.pop_front()
+    }
+
+    fn finish(&mut self) -> Vec<Option<char>> {
+        if self.len > 0 {
+            self.emit(self.len + 1);
+        }
+        self.queue.drain(..).map(Some).collect()
+    }
+}
+
+impl ResetablePipe for Base64Encode {
+    fn reset(&mut self) {
+        self.group = 0;
+        self.len = 0;
+        self.queue.clear();
+    }
+}
+
+/// An error that occurred while decoding Base64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The character is neither part of the alphabet, nor padding or whitespace.
+    InvalidCharacter(char),
+    /// The padding is misplaced, or data follows the padding.
+    InvalidPadding,
+    /// The stream ended with an incomplete group.
+    Truncated,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::InvalidCharacter(c) => write!(f, "Invalid Base64 character {:?}", c),
+            DecodeError::InvalidPadding => write!(f, "Invalid Base64 padding"),
+            DecodeError::Truncated => write!(f, "Truncated Base64 input"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A pipe that decodes a stream of characters with the standard Base64 encoding.
+///
+/// The input items are the characters of the stream, followed by a `None` that signals the end of the stream. Whitespace is ignored. Every group of four characters is decoded into up to three bytes, and the decoded bytes are queued and returned one per call. Since fewer bytes are produced than characters are consumed, `None` is returned while a group is incomplete.
+///
+/// The padding is validated: A group may only end with one or two `=` characters if it contains three or two data characters, and no data may follow a padded group before the end of the stream. Data after a padded group is reported once and then decoded as a new group. If the stream ends with an incomplete group, a [`DecodeError::Truncated`](enum.DecodeError.html#variant.Truncated) is returned. After an error, the current group is dropped and decoding continues with the next character. After the end of the stream, a new stream may be decoded.
+///
+/// Resetting this pipe drops the partial group and all queued bytes. When this pipe is finished, the end of the stream is implied: All queued bytes are returned, followed by a [`DecodeError::Truncated`](enum.DecodeError.html#variant.Truncated) if the last group is incomplete.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut decoder = Base64Decode::new();
+/// let mut decoded = Vec::new();
+/// for c in "Zm9v\nYg==".chars() {
+///     decoded.extend(decoder.next(Some(c)));
+/// }
+/// while let Some(byte) = decoder.next(None) {
+///     decoded.push(byte);
+/// }
+///
+/// assert_eq!(vec![Ok(b'f'), Ok(b'o'), Ok(b'o'), Ok(b'b')], decoded);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Base64Decode {
+    group: u32,
+    len: usize,
+    padding: usize,
+    padded: bool,
+    queue: VecDeque<Result<u8, DecodeError>>,
+}
+
+impl Base64Decode {
+    /// Create a new decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue the first `bytes` bytes of the current group and start a new group.
+    fn emit(&mut self, bytes: usize) {
+        for i in 0..bytes {
+            self.queue.push_back(Ok((self.group >> (16 - 8 * i)) as u8));
+        }
+        self.clear_group();
+    }
+
+    /// Queue an error and drop the current group.
+    fn fail(&mut self, error: DecodeError) {
+        self.queue.push_back(Err(error));
+        self.clear_group();
+    }
+
+    fn clear_group(&mut self) {
+        self.group = 0;
+        self.len = 0;
+        self.padding = 0;
+    }
+
+    /// Report an incomplete group and prepare for a new stream.
+    fn end_stream(&mut self) {
+        if self.len > 0 || self.padding > 0 {
+            self.fail(DecodeError::Truncated);
+        }
+        self.padded = false;
+    }
+
+    fn decode_char(&mut self, c: char) {
+        if c.is_whitespace() {
+            return;
+        }
+        if c == '=' {
+            if self.len < 2 {
+                self.fail(DecodeError::InvalidPadding);
+            } else {
+                self.padding += 1;
+                if self.len + self.padding == 4 {
+                    self.emit(self.len - 1);
+                    self.padded = true;
+                }
+            }
+            return;
+        }
+        let value = match BASE64_ALPHABET.iter().position(|a| *a as char == c) {
+            Some(value) => value as u32,
+            None => return self.fail(DecodeError::InvalidCharacter(c)),
+        };
+        if self.padding > 0 {
+            return self.fail(DecodeError::InvalidPadding);
+        }
+        if self.padded {
+            self.queue.push_back(Err(DecodeError::InvalidPadding));
+            self.padded = false;
+        }
+        self.group |= value << (18 - 6 * self.len);
+        self.len += 1;
+        if self.len == 4 {
+            self.emit(3);
+        }
+    }
+}
+
+impl Pipe for Base64Decode {
+    type InputItem = Option<char>;
+    type OutputItem = Option<Result<u8, DecodeError>>;
+
+    fn next(&mut self, c: Option<char>) -> Option<Result<u8, DecodeError>> {
+        match c {
+            Some(c) => self.decode_char(c),
+            None => self.end_stream(),
+        }
+        self.queue.pop_front()
+    }
+
+    fn finish(&mut self) -> Vec<Option<Result<u8, DecodeError>>> {
+        self.end_stream();
+        self.queue.drain(..).map(Some).collect()
+    }
+}
+
+impl ResetablePipe for Base64Decode {
+    fn reset(&mut self) {
+        self.clear_group();
+        self.padded = false;
+        self.queue.clear();
+    }
+}
+
+/// The algorithm of a [`Checksum`](struct.Checksum.html) decorator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    /// The CRC-32 used by Ethernet, zlib and PNG, with the polynomial `0xEDB88320` in reversed form.
+    Crc32,
+    /// The Fletcher-16 checksum, with the two 8-bit sums combined into the lower 16 bits.
+    Fletcher16,
+    /// The wrapping sum of all bytes.
+    Additive,
+}
+
+/// The lookup table of the CRC-32, with one entry per byte value.
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Items that can be fed into a [`Checksum`](struct.Checksum.html).
+pub trait ChecksumBytes {
+    /// Return the bytes of the item.
+    fn checksum_bytes(&self) -> &[u8];
+}
+
+impl ChecksumBytes for u8 {
+    fn checksum_bytes(&self) -> &[u8] {
+        std::slice::from_ref(self)
+    }
+}
+
+impl ChecksumBytes for [u8] {
+    fn checksum_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl ChecksumBytes for Vec<u8> {
+    fn checksum_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<T: ChecksumBytes + ?Sized> ChecksumBytes for &T {
+    fn checksum_bytes(&self) -> &[u8] {
+        (**self).checksum_bytes()
+    }
+}
+
+impl<T: ChecksumBytes> ChecksumBytes for Option<T> {
+    fn checksum_bytes(&self) -> &[u8] {
+        match self {
+            Some(item) => item.checksum_bytes(),
+            None => &[],
+        }
+    }
+}
+
+/// A pipe that calculates a checksum over the output bytes of another pipe.
+///
+/// The output items of the wrapped pipe are passed through unchanged. They may be single bytes, optional bytes from a producer or chunks of bytes. The checksum can be read with [`value`](#method.value) at any time, and [`finalize`](#method.finalize) reads it and restarts the checksum for the next message. Resetting this pipe resets the wrapped pipe and restarts the checksum.
+///
+/// For more information, please see [the documentation of the `checksum` method](trait.Pipe.html#method.checksum).
+pub struct Checksum<P>
+where
+    P: Pipe,
+    P::OutputItem: ChecksumBytes,
+{
+    pipe: P,
+    algorithm: ChecksumAlgorithm,
+    state: u32,
+}
+
+impl<P> Checksum<P>
+where
+    P: Pipe,
+    P::OutputItem: ChecksumBytes,
+{
+    /// Create a new checksum decorator with the given algorithm.
+    pub fn new(pipe: P, algorithm: ChecksumAlgorithm) -> Self {
+        Self {
+            pipe,
+            algorithm,
+            state: Self::initial_state(algorithm),
+        }
+    }
+
+    fn initial_state(algorithm: ChecksumAlgorithm) -> u32 {
+        match algorithm {
+            ChecksumAlgorithm::Crc32 => 0xFFFF_FFFF,
+            ChecksumAlgorithm::Fletcher16 | ChecksumAlgorithm::Additive => 0,
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state = match self.algorithm {
+                ChecksumAlgorithm::Crc32 => {
+                    CRC32_TABLE[((self.state ^ *byte as u32) & 0xff) as usize] ^ (self.state >> 8)
+                }
+                ChecksumAlgorithm::Fletcher16 => {
+                    let low = ((self.state & 0xff) + *byte as u32) % 255;
+                    let high = ((self.state >> 8) + low) % 255;
+                    (high << 8) | low
+                }
+                ChecksumAlgorithm::Additive => self.state.wrapping_add(*byte as u32),
+            };
+        }
+    }
+
+    /// Return the algorithm of the checksum.
+    pub fn algorithm(&self) -> ChecksumAlgorithm {
+        self.algorithm
+    }
+
+    /// Return the checksum of all bytes since the last reset or finalization.
+    pub fn value(&self) -> u32 {
+        match self.algorithm {
+            ChecksumAlgorithm::Crc32 => !self.state,
+            ChecksumAlgorithm::Fletcher16 | ChecksumAlgorithm::Additive => self.state,
+        }
+    }
+
+    /// Return the checksum and restart it for the next message.
+    ///
+    /// In contrast to resetting, the wrapped pipe isn't touched.
+    pub fn finalize(&mut self) -> u32 {
+        let value = self.value();
+        self.state = Self::initial_state(self.algorithm);
+        value
+    }
+
+    /// Return a reference to the wrapped pipe.
+    pub fn inner(&self) -> &P {
+        &self.pipe
+    }
+
+    /// Return a mutable reference to the wrapped pipe.
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.pipe
+    }
+}
+
+impl<P> Pipe for Checksum<P>
+where
+    P: Pipe,
+    P::OutputItem: ChecksumBytes,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = P::OutputItem;
+
+    fn next(&mut self, item: P::InputItem) -> P::OutputItem {
+        let item = self.pipe.next(item);
+        self.update(item.checksum_bytes());
+        item
+    }
+
+    fn finish(&mut self) -> Vec<P::OutputItem> {
+        let items = self.pipe.finish();
+        for item in items.iter() {
+            self.update(item.checksum_bytes());
+        }
+        items
+    }
+}
+
+impl<P> ResetablePipe for Checksum<P>
+where
+    P: ResetablePipe,
+    P::OutputItem: ChecksumBytes,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+        self.state = Self::initial_state(self.algorithm);
+    }
+}
+
+#[cfg(test)]
+fn encode_base64(encoder: &mut Base64Encode, data: &[u8]) -> String {
+    let mut encoded: String = data.iter().filter_map(|b| encoder.next(Some(*b))).collect();
+    while let Some(c) = encoder.next(None) {
+        encoded.push(c);
+    }
+    encoded
+}
+
+#[cfg(test)]
+fn decode_base64(decoder: &mut Base64Decode, data: &str) -> Vec<Result<u8, DecodeError>> {
+    let mut decoded: Vec<Result<u8, DecodeError>> =
+        data.chars().filter_map(|c| decoder.next(Some(c))).collect();
+    while let Some(byte) = decoder.next(None) {
+        decoded.push(byte);
+    }
+    decoded
+}
+
+#[test]
+fn base64_rfc_vectors() {
+    const VECTORS: &[(&str, &str)] = &[
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    let mut encoder = Base64Encode::new();
+    let mut decoder = Base64Decode::new();
+    for (plain, encoded) in VECTORS {
+        assert_eq!(*encoded, encode_base64(&mut encoder, plain.as_bytes()));
+        let decoded: Vec<Result<u8, DecodeError>> = plain.bytes().map(Ok).collect();
+        assert_eq!(decoded, decode_base64(&mut decoder, encoded));
+    }
+}
+
+#[test]
+fn base64_round_trip() {
+    use crate::dsp::XorShift;
+
+    let mut rng = XorShift::new(64);
+    let mut encoder = Base64Encode::new();
+    let mut decoder = Base64Decode::new();
+    for len in 0..100usize {
+        let data: Vec<u8> = (0..len).map(|_| rng.next_u64() as u8).collect();
+        let encoded = encode_base64(&mut encoder, &data);
+        assert_eq!(len.div_ceil(3) * 4, encoded.len());
+
+        let decoded: Result<Vec<u8>, DecodeError> =
+            decode_base64(&mut decoder, &encoded).into_iter().collect();
+        assert_eq!(Ok(data), decoded);
+    }
+}
+
+#[test]
+fn base64_decode_errors() {
+    let mut decoder = Base64Decode::new();
+    assert_eq!(
+        vec![Err(DecodeError::InvalidCharacter('!'))],
+        decode_base64(&mut decoder, "Zm!")
+    );
+    assert_eq!(
+        vec![Err(DecodeError::InvalidPadding)],
+        decode_base64(&mut decoder, "Z=")
+    );
+    assert_eq!(
+        vec![
+            Ok(b'f'),
+            Err(DecodeError::InvalidPadding),
+            Ok(b'f'),
+            Ok(b'o')
+        ],
+        decode_base64(&mut decoder, "Zg==Zm8=")
+    );
+    assert_eq!(
+        vec![Ok(b'f'), Ok(b'o'), Ok(b'o'), Err(DecodeError::Truncated)],
+        decode_base64(&mut decoder, "Zm9vYg")
+    );
+    assert_eq!(
+        vec![Err(DecodeError::Truncated)],
+        decode_base64(&mut decoder, "Zg=")
+    );
+
+    // Resetting drops partial groups.
+    decoder.next(Some('Z'));
+    decoder.next(Some('m'));
+    decoder.reset();
+    assert_eq!(vec![Ok(b'f')], decode_base64(&mut decoder, "Zg=="));
+
+    let mut encoder = Base64Encode::new();
+    encoder.next(Some(b'x'));
+    encoder.reset();
+    assert_eq!("Zg==", encode_base64(&mut encoder, b"f"));
+}
+
+#[test]
+fn base64_finish() {
+    use crate::PipeIter;
+
+    let encoded: String = (PipeIter::new(b"f".to_vec().into_iter()).compose()
+        >> Base64Encode::new())
+    .into_iter()
+    .collect();
+    assert_eq!("Zg==", encoded);
+
+    let mut encoder = Base64Encode::new();
+    encoder.next(Some(b'f'));
+    encoder.next(Some(b'o'));
+    let encoded: String = encoder.finish().into_iter().flatten().collect();
+    assert_eq!("Zm8=", encoded);
+    assert_eq!(Vec::<Option<char>>::new(), encoder.finish());
+
+    let decoded: Vec<Result<u8, DecodeError>> = (PipeIter::new("Zg".chars()).compose()
+        >> Base64Decode::new())
+    .into_iter()
+    .collect();
+    assert_eq!(vec![Err(DecodeError::Truncated)], decoded);
+
+    let mut decoder = Base64Decode::new();
+    let mut decoded: Vec<Result<u8, DecodeError>> = "Zm9vY"
+        .chars()
+        .filter_map(|c| decoder.next(Some(c)))
+        .collect();
+    decoded.extend(decoder.finish().into_iter().flatten());
+    assert_eq!(
+        vec![Ok(b'f'), Ok(b'o'), Ok(b'o'), Err(DecodeError::Truncated)],
+        decoded
+    );
+    assert_eq!(
+        Vec::<Option<Result<u8, DecodeError>>>::new(),
+        decoder.finish()
+    );
+}
+
+#[test]
+fn checksum_crc32() {
+    use crate::{Ditto, Lazy, SliceProducer};
+
+    const DATA: &[u8] = b"123456789";
+
+    // Stream the data byte by byte through a composed pipeline.
+    let mut pipe = SliceProducer::new(DATA).compose()
+        >> Lazy::new(|byte: Option<&u8>| byte.cloned()).checksum(ChecksumAlgorithm::Crc32)
+        >> Ditto::default();
+    while pipe.next(()).is_some() {}
+    assert_eq!(0xCBF4_3926, pipe.first().second().value());
+
+    let mut producer = SliceProducer::new(DATA);
+    let mut checksum = (&mut producer).checksum(ChecksumAlgorithm::Crc32);
+    checksum.next(());
+    assert_eq!(0x83DC_EFB7, checksum.finalize());
+    while checksum.next(()).is_some() {}
+    assert_eq!(0x7195_2670, checksum.value());
+
+    // Resetting restarts the checksum along with the wrapped pipe.
+    checksum.reset();
+    assert_eq!(0, checksum.value());
+    while checksum.next(()).is_some() {}
+    assert_eq!(0xCBF4_3926, checksum.value());
+}
+
+#[test]
+fn checksum_chunks() {
+    use crate::{ChunkProducer, Ditto};
+
+    let mut pipe = ChunkProducer::new(b"abcde", 2, false).compose()
+        >> Ditto::default().checksum(ChecksumAlgorithm::Fletcher16);
+    let chunks: Vec<&[u8]> = std::iter::from_fn(|| pipe.next(())).collect();
+    assert_eq!(3, chunks.len());
+    assert_eq!(0xC8F0, pipe.second().value());
+
+    let mut pipe = Ditto::<u8>::default().checksum(ChecksumAlgorithm::Additive);
+    for byte in 0..=255u8 {
+        assert_eq!(byte, pipe.next(byte));
+    }
+    assert_eq!(255 * 128, pipe.value());
+}
@@ -1,4 +1,4 @@
-use crate::{Connector, Pipe, ResetablePipe};
+use crate::{Connector, Optional, Pipe, ResetablePipe, TryConnector};
 use std::ops::Shr;
 
 /// A composable or composed pipe.
@@ -26,6 +26,47 @@ where
     pub fn unwrap(self) -> P {
         self.pipe
     }
+
+    /// Return a reference to the inner pipe.
+    pub fn inner(&self) -> &P {
+        &self.pipe
+    }
+
+    /// Return a mutable reference to the inner pipe.
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.pipe
+    }
+}
+
+impl<P0, P1> Composed<Connector<P0, P1>>
+where
+    P0: Pipe,
+    P1: Pipe<InputItem = P0::OutputItem>,
+{
+    /// Return a reference to the first pipe of the composition.
+    pub fn first(&self) -> &P0 {
+        self.pipe.first()
+    }
+
+    /// Return a mutable reference to the first pipe of the composition.
+    pub fn first_mut(&mut self) -> &mut P0 {
+        self.pipe.first_mut()
+    }
+
+    /// Return a reference to the last pipe of the composition.
+    pub fn second(&self) -> &P1 {
+        self.pipe.second()
+    }
+
+    /// Return a mutable reference to the last pipe of the composition.
+    pub fn second_mut(&mut self) -> &mut P1 {
+        self.pipe.second_mut()
+    }
+
+    /// Take the composition apart and return the first and the last pipe.
+    pub fn into_parts(self) -> (P0, P1) {
+        self.pipe.into_parts()
+    }
 }
 
 impl<P> Pipe for Composed<P>
@@ -38,6 +79,10 @@ where
     fn next(&mut self, item: P::InputItem) -> P::OutputItem {
         self.pipe.next(item)
     }
+
+    fn finish(&mut self) -> Vec<P::OutputItem> {
+        self.pipe.finish()
+    }
 }
 
 impl<P: ResetablePipe> ResetablePipe for Composed<P>
@@ -60,3 +105,336 @@ where
         Self::Output::new(Connector::new(self.pipe, other))
     }
 }
+
+/// A composable or composed fallible pipe.
+///
+/// This struct works like [`Composed`](struct.Composed.html), but the `>>` operator connects pipes using a [`TryConnector`](struct.TryConnector.html). This way, whole chains of pipes can be attached to a pipe with `Result` output items.
+///
+/// Like [`try_connect`](trait.Pipe.html#method.try_connect), the `>>` operator only short-circuits on the errors of the first pipe, so infallible stages can be attached without any adapters. If a stage is fallible itself, its `Result` is returned as the `Ok` value, so its error can be handled by the following stages.
+///
+/// For more information, please see [the documentation of the `try_compose` method](trait.Pipe.html#method.try_compose).
+pub struct TryComposed<P>
+where
+    P: Pipe,
+{
+    pipe: P,
+}
+
+impl<P> TryComposed<P>
+where
+    P: Pipe,
+{
+    /// Create new composable or composed fallible pipe.
+    pub fn new(pipe: P) -> Self {
+        TryComposed { pipe }
+    }
+
+    /// Unwrap the inner pipe.
+    pub fn unwrap(self) -> P {
+        self.pipe
+    }
+
+    /// Return a reference to the inner pipe.
+    pub fn inner(&self) -> &P {
+        &self.pipe
+    }
+
+    /// Return a mutable reference to the inner pipe.
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.pipe
+    }
+}
+
+impl<P> Pipe for TryComposed<P>
+where
+    P: Pipe,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = P::OutputItem;
+
+    fn next(&mut self, item: P::InputItem) -> P::OutputItem {
+        self.pipe.next(item)
+    }
+
+    fn finish(&mut self) -> Vec<P::OutputItem> {
+        self.pipe.finish()
+    }
+}
+
+impl<P> ResetablePipe for TryComposed<P>
+where
+    P: ResetablePipe,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+impl<P0, P1, M, E> Shr<P1> for TryComposed<P0>
+where
+    P0: Pipe<OutputItem = Result<M, E>>,
+    P1: Pipe<InputItem = M>,
+{
+    type Output = TryComposed<TryConnector<P0, P1>>;
+
+    fn shr(self, other: P1) -> Self::Output {
+        Self::Output::new(TryConnector::new(self.pipe, other))
+    }
+}
+
+/// A composable or composed optional pipe.
+///
+/// This struct works like [`Composed`](struct.Composed.html), but the `>>` operator connects pipes using the [`optional_connect`](trait.Pipe.html#method.optional_connect) method. This way, whole chains of pipes can be attached to a pipe with `Option` output items, like a [`PipeIter`](struct.PipeIter.html), without wrapping every one of them with [`optional`](trait.Pipe.html#method.optional).
+///
+/// For more information, please see [the documentation of the `optional_compose` method](trait.Pipe.html#method.optional_compose).
+pub struct OptionalComposed<P>
+where
+    P: Pipe,
+{
+    pipe: P,
+}
+
+impl<P> OptionalComposed<P>
+where
+    P: Pipe,
+{
+    /// Create new composable or composed optional pipe.
+    pub fn new(pipe: P) -> Self {
+        OptionalComposed { pipe }
+    }
+
+    /// Unwrap the inner pipe.
+    pub fn unwrap(self) -> P {
+        self.pipe
+    }
+
+    /// Return a reference to the inner pipe.
+    pub fn inner(&self) -> &P {
+        &self.pipe
+    }
+
+    /// Return a mutable reference to the inner pipe.
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.pipe
+    }
+}
+
+impl<P> Pipe for OptionalComposed<P>
+where
+    P: Pipe,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = P::OutputItem;
+
+    fn next(&mut self, item: P::InputItem) -> P::OutputItem {
+        self.pipe.next(item)
+    }
+
+    fn finish(&mut self) -> Vec<P::OutputItem> {
+        self.pipe.finish()
+    }
+}
+
+impl<P> ResetablePipe for OptionalComposed<P>
+where
+    P: ResetablePipe,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+impl<P0, P1, M> Shr<P1> for OptionalComposed<P0>
+where
+    P0: Pipe<OutputItem = Option<M>>,
+    P1: Pipe<InputItem = M>,
+{
+    type Output = OptionalComposed<Connector<P0, Optional<P1>>>;
+
+    fn shr(self, other: P1) -> Self::Output {
+        Self::Output::new(Connector::new(self.pipe, Optional::new(other)))
+    }
+}
+
+/// A boxed stage of a [`DynChain`](struct.DynChain.html).
+pub type DynStage<I> = Box<dyn ResetablePipe<InputItem = I, OutputItem = I> + Send>;
+
+/// A chain of pipes that is assembled at runtime.
+///
+/// Connecting pipes with the `>>` operator requires the structure of the chain to be known at compile time. This chain holds boxed stages instead, which all have the same input and output item type. This way, stages can be added, inserted and removed at runtime, for example from a user-edited configuration.
+///
+/// Every call to `next` passes the item through all stages in order and returns the output of the last one. An empty chain returns the item unchanged. Resetting the chain resets all stages.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut chain: DynChain<i32> = DynChain::new();
+/// assert_eq!(3, chain.next(3));
+///
+/// chain.push_stage(Lazy::new(|i: i32| i + 1));
+/// chain.push_stage(Lazy::new(|i: i32| i * 2));
+/// assert_eq!(8, chain.next(3));
+///
+/// chain.remove(0);
+/// assert_eq!(6, chain.next(3));
+/// ```
+pub struct DynChain<I> {
+    stages: Vec<DynStage<I>>,
+}
+
+impl<I> DynChain<I> {
+    /// Create a new, empty chain.
+    pub fn new() -> Self {
+        Self { stages: Vec::new() }
+    }
+
+    /// Append a stage to the end of the chain.
+    pub fn push_stage<P>(&mut self, stage: P)
+    where
+        P: ResetablePipe<InputItem = I, OutputItem = I> + Send + 'static,
+    {
+        self.stages.push(Box::new(stage));
+    }
+
+    /// Insert a stage at the given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of stages.
+    pub fn insert<P>(&mut self, index: usize, stage: P)
+    where
+        P: ResetablePipe<InputItem = I, OutputItem = I> + Send + 'static,
+    {
+        self.stages.insert(index, Box::new(stage));
+    }
+
+    /// Remove the stage at the given position and return it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> DynStage<I> {
+        self.stages.remove(index)
+    }
+
+    /// Return the number of stages.
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Return `true` if the chain has no stages.
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+}
+
+impl<I> Default for DynChain<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I> Pipe for DynChain<I> {
+    type InputItem = I;
+    type OutputItem = I;
+
+    fn next(&mut self, item: I) -> I {
+        self.stages
+            .iter_mut()
+            .fold(item, |item, stage| stage.next(item))
+    }
+
+    fn finish(&mut self) -> Vec<I> {
+        let mut items = Vec::new();
+        for stage in self.stages.iter_mut() {
+            items = items.into_iter().map(|item| stage.next(item)).collect();
+            items.extend(stage.finish());
+        }
+        items
+    }
+}
+
+impl<I> ResetablePipe for DynChain<I> {
+    fn reset(&mut self) {
+        for stage in self.stages.iter_mut() {
+            stage.reset();
+        }
+    }
+}
+
+#[test]
+fn dyn_chain() {
+    use crate::{Lazy, RunningSum};
+
+    let mut chain: DynChain<i32> = DynChain::default();
+    assert!(chain.is_empty());
+    let outputs: Vec<i32> = (0..3).map(|i| chain.next(i)).collect();
+    assert_eq!(vec![0, 1, 2], outputs);
+
+    chain.push_stage(RunningSum::new(0));
+    chain.push_stage(Lazy::new(|i: i32| i * 10));
+    let outputs: Vec<i32> = (0..3).map(|i| chain.next(i)).collect();
+    assert_eq!(vec![0, 10, 30], outputs);
+
+    chain.insert(0, Lazy::new(|i: i32| -i));
+    assert_eq!(3, chain.len());
+    let outputs: Vec<i32> = (0..3).map(|i| chain.next(i)).collect();
+    assert_eq!(vec![30, 20, 0], outputs);
+
+    let mut sum = chain.remove(1);
+    assert_eq!(3, sum.next(3));
+    assert_eq!(vec![-10, -20], vec![chain.next(1), chain.next(2)]);
+
+    chain.push_stage(RunningSum::new(0));
+    assert_eq!(-10, chain.next(1));
+    assert_eq!(-30, chain.next(2));
+    chain.reset();
+    assert_eq!(-10, chain.next(1));
+
+    let mut pipe: Box<dyn Pipe<InputItem = i32, OutputItem = i32> + Send> = Box::new(chain);
+    assert_eq!(-30, pipe.next(2));
+}
+
+#[test]
+fn optional_compose() {
+    use crate::{Lazy, PipeIter};
+
+    let mut pipe = PipeIter::new(vec![1u32, 2, 3].into_iter()).optional_compose()
+        >> Lazy::new(|i: u32| i * 2)
+        >> Lazy::new(|i: u32| i + 1);
+    assert_eq!(Some(3), pipe.next(()));
+    assert_eq!(Some(5), pipe.next(()));
+    assert_eq!(Some(7), pipe.next(()));
+    assert_eq!(None, pipe.next(()));
+
+    let mut pipe =
+        Lazy::new(|i: u32| i.checked_sub(2)).optional_compose() >> Lazy::new(|i: u32| i * 10);
+    assert_eq!(None, pipe.next(1));
+    assert_eq!(Some(10), pipe.next(3));
+    pipe.reset();
+}
+
+#[test]
+fn composed_parts() {
+    use crate::{Counter, Lazy, Metrics, ScaleOffset};
+
+    let mut pipe = Counter::new(0.0f32, 1.0).compose()
+        >> Metrics::new(Lazy::new(|i: f32| i * 2.0), |i: &f32| *i as f64)
+        >> ScaleOffset::new(1.0, 0.0);
+    assert_eq!(0.0, pipe.next(()));
+    assert_eq!(2.0, pipe.next(()));
+
+    // Read the meter in the middle of the chain.
+    assert_eq!(2, pipe.first().second().snapshot().count);
+    assert_eq!(2.0, pipe.first().second().snapshot().max);
+
+    pipe.second_mut().set_offset(10.0);
+    assert_eq!(14.0, pipe.next(()));
+    pipe.inner_mut().first_mut().first_mut().reset();
+    assert_eq!(10.0, pipe.next(()));
+
+    let (_, gain) = pipe.into_parts();
+    assert_eq!(10.0, gain.offset());
+}
@@ -0,0 +1,709 @@
+//! Signal generators.
+//!
+//! The pipes in this module generate signals like oscillators, envelopes or noise that are commonly used in audio processing. They are resetable, so a pipeline that contains generators can be restarted from the beginning.
+
+use crate::{Phasor, Pipe, ResetablePipe};
+
+/// The stage of an [`Adsr`](struct.Adsr.html) envelope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdsrStage {
+    /// The gate is closed and the envelope is silent.
+    Idle,
+    /// The envelope rises to the peak level of `1.0`.
+    Attack,
+    /// The envelope falls from the peak to the sustain level.
+    Decay,
+    /// The envelope holds the sustain level while the gate is open.
+    Sustain,
+    /// The envelope falls to zero after the gate was closed.
+    Release,
+}
+
+/// An attack-decay-sustain-release envelope generator.
+///
+/// The input item of this pipe is the gate: When the gate opens, i.e. the input item changes from `false` to `true`, the envelope rises linearly from its current level to `1.0` within `attack` calls. Then, it falls to the sustain level within `decay` calls and holds it as long as the gate is open. When the gate closes, the envelope falls linearly from its current level to zero within `release` calls.
+///
+/// Since every segment starts from the current level, opening the gate during the release or closing it during the attack or decay never causes a jump. Segments with a length of zero are skipped, so the level jumps to their end immediately. The parameters can be changed at any time and the changes also apply to the current segment.
+///
+/// Resetting this pipe returns it to the idle stage.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+/// use iterpipes::dsp::Adsr;
+///
+/// let mut adsr = Adsr::new(2, 2, 0.5, 2);
+/// let gate = [true, true, true, true, true, false, false, false];
+/// let levels: Vec<f32> = gate.iter().map(|gate| adsr.next(*gate)).collect();
+/// assert_eq!(vec![0.5, 1.0, 0.75, 0.5, 0.5, 0.25, 0.0, 0.0], levels);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Adsr {
+    attack: usize,
+    decay: usize,
+    sustain: f32,
+    release: usize,
+    stage: AdsrStage,
+    position: usize,
+    start_level: f32,
+    level: f32,
+    gate: bool,
+}
+
+impl Adsr {
+    /// Create a new envelope with the given segment lengths in calls and the given sustain level.
+    ///
+    /// The sustain level is clamped to `[0.0, 1.0]`.
+    pub fn new(attack: usize, decay: usize, sustain: f32, release: usize) -> Self {
+        Self {
+            attack,
+            decay,
+            sustain: sustain.clamp(0.0, 1.0),
+            release,
+            stage: AdsrStage::Idle,
+            position: 0,
+            start_level: 0.0,
+            level: 0.0,
+            gate: false,
+        }
+    }
+
+    /// Set the length of the attack segment in calls.
+    pub fn set_attack(&mut self, attack: usize) {
+        self.attack = attack;
+    }
+
+    /// Set the length of the decay segment in calls.
+    pub fn set_decay(&mut self, decay: usize) {
+        self.decay = decay;
+    }
+
+    /// Set the sustain level.
+    ///
+    /// The sustain level is clamped to `[0.0, 1.0]`.
+    pub fn set_sustain(&mut self, sustain: f32) {
+        self.sustain = sustain.clamp(0.0, 1.0);
+    }
+
+    /// Set the length of the release segment in calls.
+    pub fn set_release(&mut self, release: usize) {
+        self.release = release;
+    }
+
+    /// Return the current stage of the envelope.
+    pub fn stage(&self) -> AdsrStage {
+        self.stage
+    }
+
+    /// Return the current level of the envelope.
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    fn enter(&mut self, stage: AdsrStage) {
+        self.stage = stage;
+        self.position = 0;
+        self.start_level = self.level;
+    }
+}
+
+impl Pipe for Adsr {
+    type InputItem = bool;
+    type OutputItem = f32;
+
+    fn next(&mut self, gate: bool) -> f32 {
+        if gate && !self.gate {
+            self.enter(AdsrStage::Attack);
+        } else if !gate && self.gate && self.stage != AdsrStage::Idle {
+            self.enter(AdsrStage::Release);
+        }
+        self.gate = gate;
+
+        loop {
+            let (length, target, next_stage) = match self.stage {
+                AdsrStage::Idle => {
+                    self.level = 0.0;
+                    return self.level;
+                }
+                AdsrStage::Sustain => {
+                    self.level = self.sustain;
+                    return self.level;
+                }
+                AdsrStage::Attack => (self.attack, 1.0, AdsrStage::Decay),
+                AdsrStage::Decay => (self.decay, self.sustain, AdsrStage::Sustain),
+                AdsrStage::Release => (self.release, 0.0, AdsrStage::Idle),
+            };
+
+            if self.position < length {
+                self.position += 1;
+                if self.position == length {
+                    self.level = target;
+                    self.enter(next_stage);
+                } else {
+                    let progress = self.position as f32 / length as f32;
+                    self.level = self.start_level + (target - self.start_level) * progress;
+                }
+                return self.level;
+            }
+
+            // The segment is empty or was shortened, so we skip to its end.
+            self.level = target;
+            self.enter(next_stage);
+        }
+    }
+}
+
+impl ResetablePipe for Adsr {
+    fn reset(&mut self) {
+        self.stage = AdsrStage::Idle;
+        self.position = 0;
+        self.start_level = 0.0;
+        self.level = 0.0;
+        self.gate = false;
+    }
+}
+
+/// A small, self-contained pseudo-random number generator.
+///
+/// This is a xorshift64* generator: It's fast and has a period of 2^64 - 1, but it's not suited for cryptographic purposes. It's used by the [`Noise`](struct.Noise.html) generator, but may also be used to implement other random signals.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::dsp::XorShift;
+///
+/// let mut a = XorShift::new(42);
+/// let mut b = XorShift::new(42);
+/// assert_eq!(a.next_u64(), b.next_u64());
+///
+/// let value = a.next_f32();
+/// assert!(0.0 <= value && value < 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    /// The state that is used instead of a seed of zero, which would only produce zeros.
+    const ZERO_SEED_REPLACEMENT: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    /// Create a new generator from the given seed.
+    ///
+    /// Since the generator would only produce zeros from a zero state, a seed of zero is replaced by a fixed, non-zero value.
+    pub fn new(seed: u64) -> Self {
+        let state = if seed == 0 {
+            Self::ZERO_SEED_REPLACEMENT
+        } else {
+            seed
+        };
+        Self { state }
+    }
+
+    /// Return the current state of the generator.
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Generate the next random integer.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Generate the next random float in `[0.0, 1.0)`.
+    pub fn next_f32(&mut self) -> f32 {
+        // The upper 24 bits fit exactly into the mantissa of a `f32`.
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// A white noise generator.
+///
+/// Every call returns an uniformly distributed random value in `[-1.0, 1.0)`. The noise is generated by a [`XorShift`](struct.XorShift.html) generator that is seeded at construction. Resetting this pipe restores the seed, so the exact same sequence is generated again. This makes pipelines that contain noise testable.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+/// use iterpipes::dsp::Noise;
+///
+/// let mut noise = Noise::new(1234);
+/// let first: Vec<f32> = (0..16).map(|_| noise.next(())).collect();
+/// assert!(first.iter().all(|value| -1.0 <= *value && *value < 1.0));
+///
+/// noise.reset();
+/// let second: Vec<f32> = (0..16).map(|_| noise.next(())).collect();
+/// assert_eq!(first, second);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Noise {
+    seed: u64,
+    rng: XorShift,
+}
+
+impl Noise {
+    /// Create a new noise generator with the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: XorShift::new(seed),
+        }
+    }
+
+    /// Return the seed of the generator.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Return a reference to the random number generator.
+    pub fn rng(&self) -> &XorShift {
+        &self.rng
+    }
+
+    /// Return a mutable reference to the random number generator.
+    pub fn rng_mut(&mut self) -> &mut XorShift {
+        &mut self.rng
+    }
+}
+
+impl Pipe for Noise {
+    type InputItem = ();
+    type OutputItem = f32;
+
+    fn next(&mut self, _: ()) -> f32 {
+        self.rng.next_f32() * 2.0 - 1.0
+    }
+}
+
+impl ResetablePipe for Noise {
+    fn reset(&mut self) {
+        self.rng = XorShift::new(self.seed);
+    }
+}
+
+/// The phase accumulator that is shared by all oscillators.
+#[derive(Debug, Clone)]
+struct OscillatorCore {
+    phasor: Phasor,
+    sample_rate: f32,
+}
+
+impl OscillatorCore {
+    fn new(frequency: f32, sample_rate: f32) -> Self {
+        Self {
+            phasor: Phasor::new(0.0, frequency / sample_rate),
+            sample_rate,
+        }
+    }
+
+    fn frequency(&self) -> f32 {
+        self.phasor.increment() * self.sample_rate
+    }
+
+    fn set_frequency(&mut self, frequency: f32) {
+        self.phasor.set_increment(frequency / self.sample_rate);
+    }
+}
+
+/// Implement the frequency accessors and the reset of an oscillator with a `core` field.
+macro_rules! impl_oscillator {
+    ($Oscillator:ident) => {
+        impl $Oscillator {
+            /// Return the frequency of the oscillator.
+            pub fn frequency(&self) -> f32 {
+                self.core.frequency()
+            }
+
+            /// Set the frequency of the oscillator.
+            ///
+            /// The phase is kept, so the signal doesn't jump.
+            pub fn set_frequency(&mut self, frequency: f32) {
+                self.core.set_frequency(frequency);
+            }
+
+            /// Return the phase increment per call, which is the frequency divided by the sample rate.
+            pub fn increment(&self) -> f32 {
+                self.core.phasor.increment()
+            }
+
+            /// Set the phase increment per call.
+            ///
+            /// The phase is kept, so the signal doesn't jump.
+            pub fn set_increment(&mut self, increment: f32) {
+                self.core.phasor.set_increment(increment);
+            }
+        }
+
+        impl ResetablePipe for $Oscillator {
+            fn reset(&mut self) {
+                self.core.phasor.reset();
+            }
+        }
+    };
+}
+
+/// A sine wave oscillator.
+///
+/// Like all oscillators in this module, it starts with a phase of zero, which is `0.0` for a sine wave, and can either be created from a frequency and a sample rate or from a raw phase increment per call. Resetting it restores the initial phase.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+/// use iterpipes::dsp::Sine;
+///
+/// let mut sine = Sine::new(12_000.0, 48_000.0);
+/// let expected = [0.0, 1.0, 0.0, -1.0];
+/// for expected in expected.iter() {
+///     assert!((expected - sine.next(())).abs() < 1e-6);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sine {
+    core: OscillatorCore,
+}
+
+impl Sine {
+    /// Create a new sine oscillator with the given frequency and sample rate.
+    pub fn new(frequency: f32, sample_rate: f32) -> Self {
+        Self {
+            core: OscillatorCore::new(frequency, sample_rate),
+        }
+    }
+
+    /// Create a new sine oscillator with the given phase increment per call.
+    pub fn from_increment(increment: f32) -> Self {
+        Self::new(increment, 1.0)
+    }
+}
+
+impl_oscillator!(Sine);
+
+impl Pipe for Sine {
+    type InputItem = ();
+    type OutputItem = f32;
+
+    fn next(&mut self, _: ()) -> f32 {
+        let phase = self.core.phasor.next(());
+        (phase * 2.0 * std::f32::consts::PI).sin()
+    }
+}
+
+/// A square wave oscillator with a variable pulse width.
+///
+/// The output is `1.0` for the first part of every period and `-1.0` for the rest. The length of the first part relative to the whole period is the pulse width, which is `0.5` by default.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+/// use iterpipes::dsp::Square;
+///
+/// let mut square = Square::from_increment(0.25);
+/// let signal: Vec<f32> = (0..4).map(|_| square.next(())).collect();
+/// assert_eq!(vec![1.0, 1.0, -1.0, -1.0], signal);
+///
+/// square.set_pulse_width(0.25);
+/// let signal: Vec<f32> = (0..4).map(|_| square.next(())).collect();
+/// assert_eq!(vec![1.0, -1.0, -1.0, -1.0], signal);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Square {
+    core: OscillatorCore,
+    pulse_width: f32,
+}
+
+impl Square {
+    /// Create a new square oscillator with the given frequency and sample rate.
+    pub fn new(frequency: f32, sample_rate: f32) -> Self {
+        Self {
+            core: OscillatorCore::new(frequency, sample_rate),
+            pulse_width: 0.5,
+        }
+    }
+
+    /// Create a new square oscillator with the given phase increment per call.
+    pub fn from_increment(increment: f32) -> Self {
+        Self::new(increment, 1.0)
+    }
+
+    /// Return the pulse width.
+    pub fn pulse_width(&self) -> f32 {
+        self.pulse_width
+    }
+
+    /// Set the pulse width.
+    ///
+    /// The pulse width is clamped to `[0.0, 1.0]`.
+    pub fn set_pulse_width(&mut self, pulse_width: f32) {
+        self.pulse_width = pulse_width.clamp(0.0, 1.0);
+    }
+}
+
+impl_oscillator!(Square);
+
+impl Pipe for Square {
+    type InputItem = ();
+    type OutputItem = f32;
+
+    fn next(&mut self, _: ()) -> f32 {
+        if self.core.phasor.next(()) < self.pulse_width {
+            1.0
+        } else {
+            -1.0
+        }
+    }
+}
+
+/// A rising sawtooth oscillator.
+///
+/// The output rises linearly from `-1.0` to `1.0` during every period.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+/// use iterpipes::dsp::Saw;
+///
+/// let mut saw = Saw::from_increment(0.25);
+/// let signal: Vec<f32> = (0..5).map(|_| saw.next(())).collect();
+/// assert_eq!(vec![-1.0, -0.5, 0.0, 0.5, -1.0], signal);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Saw {
+    core: OscillatorCore,
+}
+
+impl Saw {
+    /// Create a new sawtooth oscillator with the given frequency and sample rate.
+    pub fn new(frequency: f32, sample_rate: f32) -> Self {
+        Self {
+            core: OscillatorCore::new(frequency, sample_rate),
+        }
+    }
+
+    /// Create a new sawtooth oscillator with the given phase increment per call.
+    pub fn from_increment(increment: f32) -> Self {
+        Self::new(increment, 1.0)
+    }
+}
+
+impl_oscillator!(Saw);
+
+impl Pipe for Saw {
+    type InputItem = ();
+    type OutputItem = f32;
+
+    fn next(&mut self, _: ()) -> f32 {
+        self.core.phasor.next(()) * 2.0 - 1.0
+    }
+}
+
+/// A triangle wave oscillator.
+///
+/// The output rises linearly from `-1.0` to `1.0` during the first half of every period and falls back to `-1.0` during the second half.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+/// use iterpipes::dsp::Triangle;
+///
+/// let mut triangle = Triangle::from_increment(0.25);
+/// let signal: Vec<f32> = (0..5).map(|_| triangle.next(())).collect();
+/// assert_eq!(vec![-1.0, 0.0, 1.0, 0.0, -1.0], signal);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Triangle {
+    core: OscillatorCore,
+}
+
+impl Triangle {
+    /// Create a new triangle oscillator with the given frequency and sample rate.
+    pub fn new(frequency: f32, sample_rate: f32) -> Self {
+        Self {
+            core: OscillatorCore::new(frequency, sample_rate),
+        }
+    }
+
+    /// Create a new triangle oscillator with the given phase increment per call.
+    pub fn from_increment(increment: f32) -> Self {
+        Self::new(increment, 1.0)
+    }
+}
+
+impl_oscillator!(Triangle);
+
+impl Pipe for Triangle {
+    type InputItem = ();
+    type OutputItem = f32;
+
+    fn next(&mut self, _: ()) -> f32 {
+        let phase = self.core.phasor.next(());
+        1.0 - 4.0 * (phase - 0.5).abs()
+    }
+}
+
+#[test]
+fn adsr_segments() {
+    let mut adsr = Adsr::new(4, 2, 0.5, 4);
+    let mut run = |gate: bool, n: usize| -> Vec<f32> { (0..n).map(|_| adsr.next(gate)).collect() };
+
+    assert_eq!(vec![0.0, 0.0], run(false, 2));
+    assert_eq!(vec![0.25, 0.5, 0.75, 1.0], run(true, 4));
+    assert_eq!(vec![0.75, 0.5], run(true, 2));
+    assert_eq!(vec![0.5, 0.5, 0.5], run(true, 3));
+    assert_eq!(vec![0.375, 0.25, 0.125, 0.0], run(false, 4));
+    assert_eq!(vec![0.0, 0.0], run(false, 2));
+
+    // Retrigger mid-decay: The gate is closed for a single call and the attack restarts from the current level.
+    let mut adsr = Adsr::new(4, 4, 0.0, 4);
+    for _ in 0..5 {
+        adsr.next(true);
+    }
+    assert_eq!(AdsrStage::Decay, adsr.stage());
+    assert_eq!(0.75, adsr.level());
+    assert_eq!(0.5625, adsr.next(false));
+    assert_eq!(AdsrStage::Release, adsr.stage());
+    let levels: Vec<f32> = (0..5).map(|_| adsr.next(true)).collect();
+    assert_eq!(vec![0.671875, 0.78125, 0.890625, 1.0, 0.75], levels);
+}
+
+#[test]
+fn adsr_transitions() {
+    // Release during the attack starts from the current level.
+    let mut adsr = Adsr::new(4, 4, 0.5, 2);
+    adsr.next(true);
+    adsr.next(true);
+    assert_eq!(AdsrStage::Attack, adsr.stage());
+    assert_eq!(
+        vec![0.25, 0.0, 0.0],
+        vec![adsr.next(false), adsr.next(false), adsr.next(false)]
+    );
+    assert_eq!(AdsrStage::Idle, adsr.stage());
+
+    // Zero-length segments are skipped.
+    let mut adsr = Adsr::new(0, 0, 0.5, 0);
+    assert_eq!(0.5, adsr.next(true));
+    assert_eq!(AdsrStage::Sustain, adsr.stage());
+    assert_eq!(0.0, adsr.next(false));
+    assert_eq!(AdsrStage::Idle, adsr.stage());
+
+    let mut adsr = Adsr::new(0, 2, 0.0, 0);
+    assert_eq!(
+        vec![0.5, 0.0, 0.0],
+        vec![adsr.next(true), adsr.next(true), adsr.next(true)]
+    );
+
+    // Resetting returns to the idle stage.
+    adsr.set_attack(2);
+    adsr.next(false);
+    adsr.next(true);
+    adsr.reset();
+    assert_eq!(AdsrStage::Idle, adsr.stage());
+    assert_eq!(0.0, adsr.next(false));
+    assert_eq!(0.5, adsr.next(true));
+}
+
+#[test]
+fn noise_determinism() {
+    use crate::testing::assert_reset_law;
+
+    assert_reset_law(Noise::new(7), vec![(); 1024]);
+
+    let mut a = Noise::new(7);
+    let mut b = Noise::new(7);
+    let mut c = Noise::new(8);
+    let a: Vec<f32> = (0..64).map(|_| a.next(())).collect();
+    let b: Vec<f32> = (0..64).map(|_| b.next(())).collect();
+    let c: Vec<f32> = (0..64).map(|_| c.next(())).collect();
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+
+    // A seed of zero doesn't get stuck.
+    let mut zero = Noise::new(0);
+    assert_ne!(zero.next(()), zero.next(()));
+}
+
+#[test]
+fn noise_range() {
+    let mut noise = Noise::new(0xDEAD_BEEF);
+    let mut min = f32::INFINITY;
+    let mut max = f32::NEG_INFINITY;
+    let mut sum = 0.0f64;
+    const N: usize = 100_000;
+    for _ in 0..N {
+        let value = noise.next(());
+        assert!((-1.0..1.0).contains(&value));
+        min = min.min(value);
+        max = max.max(value);
+        sum += value as f64;
+    }
+    assert!(min < -0.99 && max > 0.99);
+    assert!((sum / N as f64).abs() < 0.01);
+}
+
+#[test]
+fn oscillator_periods() {
+    // 375 Hz at 48 kHz is a period of 128 calls.
+    let mut sine = Sine::new(375.0, 48_000.0);
+    let mut square = Square::new(375.0, 48_000.0);
+    let mut saw = Saw::new(375.0, 48_000.0);
+    let mut triangle = Triangle::new(375.0, 48_000.0);
+
+    let run = |pipe: &mut dyn Pipe<InputItem = (), OutputItem = f32>| -> Vec<f32> {
+        (0..1024).map(|_| pipe.next(())).collect()
+    };
+    for signal in [
+        run(&mut sine),
+        run(&mut square),
+        run(&mut saw),
+        run(&mut triangle),
+    ]
+    .iter()
+    {
+        assert!(signal.iter().all(|value| (-1.0..=1.0).contains(value)));
+        for i in 0..896 {
+            assert!((signal[i] - signal[i + 128]).abs() < 1e-3);
+        }
+    }
+
+    // Exactly half of every period is positive.
+    let signal = run(&mut square);
+    assert_eq!(512, signal.iter().filter(|value| **value > 0.0).count());
+    square.set_pulse_width(0.25);
+    let signal = run(&mut square);
+    assert_eq!(256, signal.iter().filter(|value| **value > 0.0).count());
+}
+
+#[test]
+fn oscillator_frequency_change() {
+    let mut saw = Saw::new(100.0, 1000.0);
+    assert_eq!(100.0, saw.frequency());
+    assert_eq!(0.1, saw.increment());
+    for _ in 0..3 {
+        saw.next(());
+    }
+
+    // The phase continues from where it was, only the step size changes.
+    saw.set_frequency(50.0);
+    assert_eq!(0.05, saw.increment());
+    let signal: Vec<f32> = (0..3).map(|_| saw.next(())).collect();
+    assert!((signal[0] - -0.4).abs() < 1e-6);
+    assert!((signal[1] - -0.3).abs() < 1e-6);
+    assert!((signal[2] - -0.2).abs() < 1e-6);
+
+    saw.reset();
+    assert_eq!(-1.0, saw.next(()));
+    assert_eq!(50.0, saw.frequency());
+
+    let mut sine = Sine::from_increment(0.25);
+    sine.next(());
+    sine.set_increment(0.125);
+    assert!((1.0 - sine.next(())).abs() < 1e-6);
+    assert!((0.5f32.sqrt() - sine.next(())).abs() < 1e-6);
+}
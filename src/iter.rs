@@ -25,13 +25,16 @@ impl<I: Iterator> Pipe for PipeIter<I> {
 
 /// An iterator that yields values by creating a default value and running it through a pipe.
 ///
-/// The input value for the pipe obviously must implement `Default` and the output item of the pipe must be an `Option<T>`.
+/// The input value for the pipe obviously must implement `Default` and the output item of the pipe must be an `Option<T>`. Once the pipe returns `None`, it is [finished](trait.Pipe.html#method.finish) and the remaining items are yielded before the iteration ends. `None` items among the remaining items are skipped.
+///
+/// This iterator is fused: After the first `None` of the pipe, the pipe is never advanced again, even if it would return `Some` item later on.
 pub struct IterPipe<P>
 where
     P: Pipe,
     P::InputItem: Default,
 {
     pipe: P,
+    tail: Option<std::vec::IntoIter<P::OutputItem>>,
 }
 
 impl<P> IterPipe<P>
@@ -41,7 +44,7 @@ where
 {
     /// Create a new iterator with that pipe.
     pub fn new(pipe: P) -> Self {
-        Self { pipe }
+        Self { pipe, tail: None }
     }
 }
 
@@ -53,6 +56,53 @@ where
     type Item = O;
 
     fn next(&mut self) -> Option<O> {
-        self.pipe.next(P::InputItem::default())
+        if self.tail.is_none() {
+            match self.pipe.next(P::InputItem::default()) {
+                Some(item) => return Some(item),
+                None => self.tail = Some(self.pipe.finish().into_iter()),
+            }
+        }
+        self.tail.as_mut()?.flatten().next()
+    }
+}
+
+#[test]
+fn iter_pipe_finish() {
+    use crate::Lazy;
+    use std::cell::Cell;
+
+    /// A pipe that returns `None` on every third call and holds back two items.
+    struct Gaps(u32);
+
+    impl Pipe for Gaps {
+        type InputItem = ();
+        type OutputItem = Option<u32>;
+
+        fn next(&mut self, _: ()) -> Option<u32> {
+            self.0 += 1;
+            if self.0.is_multiple_of(3) {
+                None
+            } else {
+                Some(self.0)
+            }
+        }
+
+        fn finish(&mut self) -> Vec<Option<u32>> {
+            vec![Some(10), None, Some(20)]
+        }
     }
+
+    let mut iter = IterPipe::new(Gaps(0));
+    let items: Vec<u32> = iter.by_ref().collect();
+    assert_eq!(vec![1, 2, 10, 20], items);
+    assert_eq!(None, iter.next());
+
+    let calls = Cell::new(0);
+    let mut iter = IterPipe::new(Lazy::new(|_: ()| {
+        calls.set(calls.get() + 1);
+        None::<u32>
+    }));
+    assert_eq!(None, iter.next());
+    assert_eq!(None, iter.next());
+    assert_eq!(1, calls.get());
 }
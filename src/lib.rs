@@ -39,10 +39,11 @@
 //!
 //! Secondly, you can compose them using the `>>` operator. Prior to this, you have to turn the first pipe of the composition into a composable one using the [`compose` method](trait.Pipe.html#method.compose). Then, you can connect fitting pipes together into a big one.
 //!
-//! Let's reuse the `Multiply` pipe from above and apply it to a pulse wave generator:
+//! Let's reuse the `Multiply` pipe from above and apply it to the square wave generator from the [`dsp` module](dsp/index.html):
 //!
 //! ```
 //! use iterpipes::Pipe;
+//! use iterpipes::dsp::Square;
 //! use std::ops::Mul;
 //!
 //! /// A pipe that multiplies any signal by a given factor.
@@ -60,31 +61,11 @@
 //!     }
 //! }
 //!
-//! /// A pipe that generates a square wave from a given index.
-//! struct PulseWave {
-//!     pulse_length: usize,
-//! }
-//!
-//! impl Pipe for PulseWave {
-//!     type InputItem = usize;
-//!     type OutputItem = f32;
-//!
-//!     #[inline]
-//!     fn next(&mut self, index: usize) -> f32 {
-//!         // If the index is part of an even pulse, return 1.0 and -1.0 otherwise.
-//!         if (index / self.pulse_length) % 2 == 0 {
-//!             1.0
-//!         } else {
-//!             -1.0
-//!         }
-//!     }
-//! }
-//!
-//! // Compose the two pipes into one.
-//! let mut combined = PulseWave { pulse_length: 2 }.compose() >> Multiply { factor: 0.5 };
+//! // Compose the two pipes into one. The square wave has a period of four items.
+//! let mut combined = Square::from_increment(0.25).compose() >> Multiply { factor: 0.5 };
 //!
 //! for i in 0..32 {
-//!     let frame = combined.next(i);
+//!     let frame = combined.next(());
 //!     if (i / 2) % 2 == 0 {
 //!         assert_eq!(frame, 0.5);
 //!     } else {
@@ -122,10 +103,10 @@
 //!
 //! // Create an iterator over the input.
 //! let pipeline = input.iter().cloned();
-//! // Turn it into a pipe.
-//! let pipeline = PipeIter::new(pipeline).compose();
-//! // Connect it to an optional version of the multiplication pipe.
-//! let pipeline = pipeline >> Multiply { factor: 2}.optional();
+//! // Turn it into a pipe that skips all following pipes once the iterator is exhausted.
+//! let pipeline = PipeIter::new(pipeline).optional_compose();
+//! // Connect it to the multiplication pipe.
+//! let pipeline = pipeline >> Multiply { factor: 2 };
 //! // Turn the pipe back to an iterator.
 //! let pipeline = pipeline.into_iter();
 //!
@@ -151,6 +132,8 @@
 //! assert_eq!(super_pipe.next((2.0, 1, true)), (4.0, 3, false));
 //! ```
 //!
+//! When a tuple of pipes is [finished](trait.Pipe.html#method.finish), all of its pipes are finished and their remaining items are zipped. If some pipes have more remaining items than others, their surplus items are dropped.
+//!
 //! # A note on performance
 //!
 //! Using pipes to express processing streams has side-effects on the performance. Since the resulting algorithm is created from many small functions instead of one big one, there is an overhead when these functions are called. It might also be harder for the compiler to use [SIMD instructions](https://en.wikipedia.org/wiki/SIMD).
@@ -179,6 +162,60 @@ pub trait Pipe {
     /// Calculate the next output item, based on an input item.
     fn next(&mut self, item: Self::InputItem) -> Self::OutputItem;
 
+    /// Signal the end of the stream and return the remaining output items.
+    ///
+    /// Some pipes hold back data until more input items arrive, for example to group items into chunks. Once the stream ends, this data would get lost, since `next` won't be called again. Instead, `finish` should be called, which returns all output items that still can be calculated. The default implementation returns no items.
+    ///
+    /// Connected pipes finish in a cascade: The first pipe is finished, its remaining items are pushed through the second pipe and then the second pipe is finished. Decorators finish the pipe they wrap and map its remaining items the same way they map the output items of `next`. An [`IterPipe`](struct.IterPipe.html) calls `finish` once the pipe returned `None` and yields the remaining items before it ends.
+    ///
+    /// Some pipes can't map all remaining items and drop some of them:
+    ///
+    /// * A tuple of pipes zips the remaining items of its pipes, so the surplus items of pipes with more remaining items than the others are dropped.
+    /// * A [`Bypass`](struct.Bypass.html) drops all remaining items of the internal pipe, since there are no input items to pair them with.
+    /// * A [`BypassWith`](struct.BypassWith.html) drops all remaining items of the internal pipe, since there are no kept parts to pair them with.
+    /// * A [`MapFst`](struct.MapFst.html) or a [`MapSnd`](struct.MapSnd.html) drops all remaining items of the wrapped pipe, since there are no other elements to pair them with.
+    /// * A [`Pulled`](struct.Pulled.html) producer doesn't finish its upstream producer, so the items that an upstream pipe holds back are lost.
+    /// * A [`Crossfade`](struct.Crossfade.html) blends the remaining items of both pipes pairwise, so the surplus items of one pipe are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// /// A pipe that sums up blocks of two items.
+    /// struct PairSum {
+    ///     held: Option<u32>,
+    /// }
+    ///
+    /// impl Pipe for PairSum {
+    ///     type InputItem = u32;
+    ///     type OutputItem = Option<u32>;
+    ///
+    ///     fn next(&mut self, item: u32) -> Option<u32> {
+    ///         match self.held.take() {
+    ///             Some(held) => Some(held + item),
+    ///             None => {
+    ///                 self.held = Some(item);
+    ///                 None
+    ///             }
+    ///         }
+    ///     }
+    ///
+    ///     fn finish(&mut self) -> Vec<Option<u32>> {
+    ///         vec![self.held.take()]
+    ///     }
+    /// }
+    ///
+    /// let mut pipe = PairSum { held: None };
+    /// assert_eq!(None, pipe.next(1));
+    /// assert_eq!(Some(3), pipe.next(2));
+    /// assert_eq!(None, pipe.next(3));
+    /// assert_eq!(vec![Some(3)], pipe.finish());
+    /// ```
+    fn finish(&mut self) -> Vec<Self::OutputItem> {
+        Vec::new()
+    }
+
     /// Create a bypassed version of the pipe.
     ///
     /// The returned pipe clones the input item, calculates the next output item and returns both
@@ -213,6 +250,35 @@ pub trait Pipe {
         Bypass::new(self)
     }
 
+    /// Create a bypassed version of the pipe with a custom splitting function.
+    ///
+    /// The returned pipe splits the input item into a kept part and a forwarded part using the `split` function. The forwarded part is processed by the pipe and the kept part is returned together with the output item. In contrast to [`bypass`](#method.bypass), the input item doesn't need to implement `Clone`, which makes this usable for large or borrowed items. `bypass` behaves like `bypass_with(|i| (i.clone(), i))`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// /// A large event that shouldn't be cloned.
+    /// struct Event {
+    ///     timestamp: u64,
+    ///     payload: Vec<f32>,
+    /// }
+    ///
+    /// let mut pipe = Lazy::new(|payload: Vec<f32>| payload.iter().sum::<f32>())
+    ///     .bypass_with(|event: Event| (event.timestamp, event.payload));
+    ///
+    /// let event = Event { timestamp: 42, payload: vec![1.0, 2.0] };
+    /// assert_eq!((42, 3.0), pipe.next(event));
+    /// ```
+    fn bypass_with<I, K, F>(self, split: F) -> BypassWith<Self, I, K, F>
+    where
+        Self: Sized,
+        F: FnMut(I) -> (K, Self::InputItem),
+    {
+        BypassWith::new(self, split)
+    }
+
     /// Create a composable pipe.
     ///
     /// Composable pipes implement the `>>` operator that concatenates pipes.
@@ -252,8 +318,7 @@ pub trait Pipe {
     ///     }
     /// }
     ///
-    /// let mut pipe = PipeIter::new(0..).compose()
-    ///     >> Lazy::new(|i: Option<usize>| i.unwrap())
+    /// let mut pipe = PipeIter::new(0..).unwrap_or_default().compose()
     ///     >> Progress {period_length: 4}.compose()
     ///     >> SquareWave;
     ///
@@ -326,6 +391,228 @@ pub trait Pipe {
         Connector::new(self, other)
     }
 
+    /// Map the errors of a pipe's result output items.
+    ///
+    /// The decorated pipe applies the given function to every error of the original pipe and leaves `Ok` items untouched, just like [`Result::map_err`](https://doc.rust-lang.org/std/result/enum.Result.html#method.map_err).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Lazy::new(|i: &str| i.parse::<u8>()).map_err(|_| "not a number");
+    /// assert_eq!(Ok(42), pipe.next("42"));
+    /// assert_eq!(Err("not a number"), pipe.next("foo"));
+    /// ```
+    fn map_err<T, E, E2, F>(self, function: F) -> MapErr<Self, F>
+    where
+        Self: Sized + Pipe<OutputItem = Result<T, E>>,
+        F: FnMut(E) -> E2,
+    {
+        MapErr::new(self, function)
+    }
+
+    /// Convert the errors of a pipe's result output items using `From`.
+    ///
+    /// The decorated pipe converts every error of the original pipe into `E2`, just like the `?` operator does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    /// use std::num::ParseIntError;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Error(ParseIntError);
+    ///
+    /// impl From<ParseIntError> for Error {
+    ///     fn from(error: ParseIntError) -> Self {
+    ///         Error(error)
+    ///     }
+    /// }
+    ///
+    /// let mut pipe = Lazy::new(|i: &str| i.parse::<u8>()).err_into::<Error>();
+    /// assert_eq!(Ok(42), pipe.next("42"));
+    /// assert!(pipe.next("foo").is_err());
+    /// ```
+    fn err_into<E2>(self) -> ErrInto<Self, E2>
+    where
+        Self: Sized,
+    {
+        ErrInto::new(self)
+    }
+
+    /// Convert the output items of a pipe using `TryFrom`.
+    ///
+    /// The decorated pipe tries to convert every output item of the original pipe into `U` and returns the result. This way, narrowing conversions surface their errors instead of silently truncating the items, and the errors can be handled with the other methods for fallible pipes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Ditto::<i64>::default().try_convert::<i16>();
+    /// assert_eq!(Ok(1024), pipe.next(1024));
+    /// assert!(pipe.next(1 << 20).is_err());
+    /// ```
+    fn try_convert<U>(self) -> TryConvert<Self, U>
+    where
+        Self: Sized,
+        U: std::convert::TryFrom<Self::OutputItem>,
+    {
+        TryConvert::new(self)
+    }
+
+    /// Retry a fallible pipe.
+    ///
+    /// If the pipe returns an error, the decorated pipe invokes it again with a clone of the same input item, up to `retries` times. If all attempts fail, the last error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut attempts: usize = 0;
+    /// let mut pipe = LazyMut::new(|i: u8| {
+    ///     attempts += 1;
+    ///     if attempts % 3 == 0 {
+    ///         Ok(i)
+    ///     } else {
+    ///         Err(attempts)
+    ///     }
+    /// })
+    /// .retry(2);
+    ///
+    /// assert_eq!(Ok(42), pipe.next(42));
+    /// ```
+    fn retry<T, E>(self, retries: usize) -> Retry<Self, fn(usize, &E)>
+    where
+        Self: Sized + Pipe<OutputItem = Result<T, E>>,
+        Self::InputItem: Clone,
+    {
+        Retry::new(self, retries, ignore_error)
+    }
+
+    /// Retry a fallible pipe and call a hook before every retry.
+    ///
+    /// This decorator behaves like the one created by [`retry`](#method.retry), but before every retry, the hook is called with the number of the failed attempt and the error. This may be used to log the error or to sleep before the next attempt.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut log: Vec<String> = Vec::new();
+    /// let mut pipe = Lazy::new(|i: &str| i.parse::<u8>())
+    ///     .retry_with(2, |attempt, error| log.push(format!("attempt {}: {}", attempt, error)));
+    ///
+    /// assert!(pipe.next("foo").is_err());
+    /// drop(pipe);
+    ///
+    /// assert_eq!(2, log.len());
+    /// ```
+    fn retry_with<T, E, F>(self, retries: usize, hook: F) -> Retry<Self, F>
+    where
+        Self: Sized + Pipe<OutputItem = Result<T, E>>,
+        Self::InputItem: Clone,
+        F: FnMut(usize, &E),
+    {
+        Retry::new(self, retries, hook)
+    }
+
+    /// Connect a fallible pipe to another pipe.
+    ///
+    /// The created pipe takes an input item for `self` and calculates the intermediate result. If it's `Ok(value)`, the value is used to calculate the output value of the `other` pipe, which is then returned as `Ok`. If it's an error, the `other` pipe is not invoked and the error is returned unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Lazy::new(|i: &str| i.parse::<u8>())
+    ///     .try_connect(Lazy::new(|i: u8| i / 2));
+    ///
+    /// assert_eq!(Ok(21), pipe.next("42"));
+    /// assert!(pipe.next("foo").is_err());
+    /// ```
+    fn try_connect<O, M, E>(self, other: O) -> TryConnector<Self, O>
+    where
+        Self: Sized + Pipe<OutputItem = Result<M, E>>,
+        O: Pipe<InputItem = M>,
+    {
+        TryConnector::new(self, other)
+    }
+
+    /// Create a composable fallible pipe.
+    ///
+    /// Composable fallible pipes implement the `>>` operator that connects pipes with the [`try_connect`](#method.try_connect) method.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Lazy::new(|i: &str| i.parse::<u8>()).try_compose()
+    ///     >> Lazy::new(|i: u8| i / 2)
+    ///     >> Lazy::new(|i: u8| i + 1);
+    ///
+    /// assert_eq!(Ok(22), pipe.next("42"));
+    /// assert!(pipe.next("foo").is_err());
+    /// ```
+    fn try_compose(self) -> TryComposed<Self>
+    where
+        Self: Sized,
+    {
+        TryComposed::new(self)
+    }
+
+    /// Connect a pipe with optional output items to another pipe.
+    ///
+    /// The created pipe takes an input item for `self` and calculates the intermediate item. If it's `Some(value)`, the value is used to calculate the output item of the `other` pipe, which is then returned as `Some`. If it's `None`, the `other` pipe is not invoked and `None` is returned. This is the same as connecting `self` to the [`optional`](#method.optional) version of `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = PipeIter::new(0..2).optional_connect(Lazy::new(|i: i32| i * 2));
+    ///
+    /// assert_eq!(Some(0), pipe.next(()));
+    /// assert_eq!(Some(2), pipe.next(()));
+    /// assert_eq!(None, pipe.next(()));
+    /// ```
+    fn optional_connect<O, M>(self, other: O) -> Connector<Self, Optional<O>>
+    where
+        Self: Sized + Pipe<OutputItem = Option<M>>,
+        O: Pipe<InputItem = M>,
+    {
+        Connector::new(self, Optional::new(other))
+    }
+
+    /// Create a composable optional pipe.
+    ///
+    /// Composable optional pipes implement the `>>` operator that connects pipes with the [`optional_connect`](#method.optional_connect) method. The normal [`compose`](#method.compose) can't do this automatically, since it would need to decide whether to lift the next pipe depending on its input item type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = PipeIter::new(0..2).optional_compose()
+    ///     >> Lazy::new(|i: i32| i * 2)
+    ///     >> Lazy::new(|i: i32| i + 1);
+    ///
+    /// assert_eq!(Some(1), pipe.next(()));
+    /// assert_eq!(Some(3), pipe.next(()));
+    /// assert_eq!(None, pipe.next(()));
+    /// ```
+    fn optional_compose(self) -> OptionalComposed<Self>
+    where
+        Self: Sized,
+    {
+        OptionalComposed::new(self)
+    }
+
     /// Wrap the pipe into an iterator.
     ///
     /// For example, this can be used to iterate over a pipeline in a `for` loop. The input item needs to have a default value, since the iterator has to create it on it's own, and the output item must be an `Option`al value.
@@ -417,21 +704,7 @@ pub trait Pipe {
     /// ```
     /// use iterpipes::*;
     ///
-    /// /// A pipe that always returns a clone of the same value.
-    /// struct DefaultPipe<T: Clone> {
-    ///     value: T,
-    /// }
-    ///
-    /// impl<T: Clone> Pipe for DefaultPipe<T> {
-    ///     type InputItem = ();
-    ///     type OutputItem = T;
-    ///
-    ///     fn next(&mut self, _: ()) -> T {
-    ///         self.value.clone()
-    ///     }
-    /// }
-    ///
-    /// let mut pipe = DefaultPipe { value: 42u8 }.enumerate();
+    /// let mut pipe = Const::new(42u8).enumerate();
     /// assert_eq!((0, 42), pipe.next(()));
     /// assert_eq!((1, 42), pipe.next(()));
     /// assert_eq!((2, 42), pipe.next(()));
@@ -443,103 +716,749 @@ pub trait Pipe {
         Enumerate::new(self)
     }
 
-    /// Create a boxed trait object of the pipe.
+    /// Unwrap the optional output items of a pipe or use a default value.
     ///
-    /// This might be useful to move pipes across API bounds since it hides the internal composition of the pipe.
+    /// The decorated pipe returns the contained value if the original pipe returns `Some(value)` and a clone of the given default value if the original pipe returns `None`. This closes the `Option` layer introduced by iterators in a controlled way.
     ///
     /// # Example
     ///
     /// ```
     /// use iterpipes::*;
     ///
-    /// fn create_pipe() -> Box<dyn Pipe<InputItem = usize, OutputItem = usize>> {
-    ///     Lazy::new(|i| i * 2).boxed()
-    /// }
+    /// let mut pipe = PipeIter::new(0..2).unwrap_or(42);
+    /// assert_eq!(0, pipe.next(()));
+    /// assert_eq!(1, pipe.next(()));
+    /// assert_eq!(42, pipe.next(()));
+    /// ```
+    fn unwrap_or<T>(self, default: T) -> UnwrapOr<Self, T>
+    where
+        Self: Sized + Pipe<OutputItem = Option<T>>,
+        T: Clone,
+    {
+        UnwrapOr::new(self, default)
+    }
+
+    /// Unwrap the optional output items of a pipe or use the default value of the type.
     ///
-    /// let mut pipe = create_pipe();
+    /// The decorated pipe returns the contained value if the original pipe returns `Some(value)` and `T::default()` if the original pipe returns `None`.
+    ///
+    /// # Example
     ///
-    /// for i in 0..4 {
-    ///     assert_eq!(i*2, pipe.next(i));
-    /// }
     /// ```
-    fn boxed(self) -> Box<dyn Pipe<InputItem = Self::InputItem, OutputItem = Self::OutputItem>>
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = PipeIter::new(1..2).unwrap_or_default();
+    /// assert_eq!(1, pipe.next(()));
+    /// assert_eq!(0, pipe.next(()));
+    /// ```
+    fn unwrap_or_default<T>(self) -> UnwrapOrDefault<Self>
     where
-        Self: Sized + 'static,
+        Self: Sized + Pipe<OutputItem = Option<T>>,
+        T: Default,
     {
-        Box::new(self)
+        UnwrapOrDefault::new(self)
     }
-}
 
-/// A pipe that can be reseted to its initial state.
-///
-/// Pipes may have an internal state which they also may modify with every item they process. However, there a situations where wants to reset a pipeline to it's initial state without needing to reconstruct it. Pipes that have the ability to be reseted to their inital state implement this trait. After [`reset`](#method.reset) was called, the pipe is reseted to it's construction state.
-///
-/// Many decorators implement `ResetablePipe` where possible, which means that pipelines that were constructed from resetable pipes will be resetable too. One major exception is the [`LazyMut`](struct.LazyMut.html) pipe. Since the function object may modify it's state and doesn't offer a way to reset this state, a pipeline that contains a `LazyMut` is not resetable.
-pub trait ResetablePipe: Pipe {
-    /// Reset the state of the pipe.
+    /// Unwrap the optional output items of a pipe or compute a value from a closure.
     ///
-    /// If implemented, this method resets the pipe to the state it had before the first output was retrieved. Since all decorator pipes of this crate implement it, it can be be used to reset the state of a whole pipeline without needing to constructing it again.
-    ///
-    /// If your pipe can't be reseted, you may use the `unimplemented!()` macro. However, you should note this behavior in your documentation!
+    /// The decorated pipe returns the contained value if the original pipe returns `Some(value)` and calls the given function if the original pipe returns `None`.
     ///
     /// # Example
     ///
     /// ```
     /// use iterpipes::*;
     ///
-    /// /// A pipe that counts up.
-    /// struct Counter {
-    ///     index: usize,
-    /// }
+    /// let mut misses: usize = 0;
+    /// let mut pipe = PipeIter::new(1..2).unwrap_or_else(|| {
+    ///     misses += 1;
+    ///     0
+    /// });
+    /// assert_eq!(1, pipe.next(()));
+    /// assert_eq!(0, pipe.next(()));
+    /// assert_eq!(0, pipe.next(()));
+    /// drop(pipe);
+    ///
+    /// assert_eq!(2, misses);
+    /// ```
+    fn unwrap_or_else<T, F>(self, function: F) -> UnwrapOrElse<Self, F>
+    where
+        Self: Sized + Pipe<OutputItem = Option<T>>,
+        F: FnMut() -> T,
+    {
+        UnwrapOrElse::new(self, function)
+    }
+
+    /// Turn the optional output items of a pipe into results.
     ///
-    /// impl Pipe for Counter {
-    ///     type InputItem = ();
-    ///     type OutputItem = usize;
+    /// The decorated pipe returns `Ok(value)` if the original pipe returns `Some(value)` and `Err` with a clone of the given error if the original pipe returns `None`.
     ///
-    ///     fn next(&mut self, _: ()) -> usize {
-    ///         let output = self.index;
-    ///         self.index += 1;
-    ///         output
-    ///     }
-    /// }
+    /// # Example
     ///
-    /// impl ResetablePipe for Counter {
-    ///     fn reset(&mut self) {
-    ///         self.index = 0;
-    ///     }
-    /// }
+    /// ```
+    /// use iterpipes::*;
     ///
-    /// let mut counter = Counter { index: 0};
-    /// assert_eq!(0, counter.next(()));
-    /// assert_eq!(1, counter.next(()));
-    /// counter.reset();
-    /// assert_eq!(0, counter.next(()));
-    /// assert_eq!(1, counter.next(()));
+    /// let mut pipe = PipeIter::new(0..1).ok_or("end of stream");
+    /// assert_eq!(Ok(0), pipe.next(()));
+    /// assert_eq!(Err("end of stream"), pipe.next(()));
     /// ```
-    fn reset(&mut self);
-}
-
-impl Pipe for () {
-    type InputItem = ();
-    type OutputItem = ();
-
-    fn next(&mut self, _: ()) {}
-}
+    fn ok_or<T, E>(self, error: E) -> OkOr<Self, E>
+    where
+        Self: Sized + Pipe<OutputItem = Option<T>>,
+        E: Clone,
+    {
+        OkOr::new(self, error)
+    }
 
-impl ResetablePipe for () {
+    /// Turn the result output items of a pipe into optional items.
+    ///
+    /// The decorated pipe returns `Some(value)` if the original pipe returns `Ok(value)` and `None` if it returns an error. The error is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Lazy::new(|i: &str| i.parse::<u8>()).ok();
+    /// assert_eq!(Some(42), pipe.next("42"));
+    /// assert_eq!(None, pipe.next("foo"));
+    /// ```
+    fn ok<T, E>(self) -> ResultOk<Self, fn(E)>
+    where
+        Self: Sized + Pipe<OutputItem = Result<T, E>>,
+    {
+        ResultOk::new(self, drop)
+    }
+
+    /// Turn the result output items of a pipe into optional items and handle the errors.
+    ///
+    /// This decorator behaves like the one created by [`ok`](#method.ok), but every error is passed to the given callback before it is discarded, for example to log it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut errors: Vec<String> = Vec::new();
+    /// let mut pipe = Lazy::new(|i: &str| i.parse::<u8>())
+    ///     .ok_with(|error| errors.push(error.to_string()));
+    /// assert_eq!(Some(42), pipe.next("42"));
+    /// assert_eq!(None, pipe.next("foo"));
+    /// drop(pipe);
+    ///
+    /// assert_eq!(1, errors.len());
+    /// ```
+    fn ok_with<T, E, F>(self, callback: F) -> ResultOk<Self, F>
+    where
+        Self: Sized + Pipe<OutputItem = Result<T, E>>,
+        F: FnMut(E),
+    {
+        ResultOk::new(self, callback)
+    }
+
+    /// Transpose the `Option<Result<T, E>>` output items of a pipe into `Result<Option<T>, E>` items.
+    ///
+    /// This applies [`Option::transpose`](https://doc.rust-lang.org/std/option/enum.Option.html#method.transpose) to every output item.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = PipeIter::new(vec![Ok(1), Err(())].into_iter()).transpose_option();
+    /// assert_eq!(Ok(Some(1)), pipe.next(()));
+    /// assert_eq!(Err(()), pipe.next(()));
+    /// assert_eq!(Ok(None), pipe.next(()));
+    /// ```
+    fn transpose_option<T, E>(self) -> TransposeOption<Self>
+    where
+        Self: Sized + Pipe<OutputItem = Option<Result<T, E>>>,
+    {
+        TransposeOption::new(self)
+    }
+
+    /// Transpose the `Result<Option<T>, E>` output items of a pipe into `Option<Result<T, E>>` items.
+    ///
+    /// This applies [`Result::transpose`](https://doc.rust-lang.org/std/result/enum.Result.html#method.transpose) to every output item.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Ditto::<Result<Option<u8>, ()>>::default().transpose_result();
+    /// assert_eq!(Some(Ok(1)), pipe.next(Ok(Some(1))));
+    /// assert_eq!(Some(Err(())), pipe.next(Err(())));
+    /// assert_eq!(None, pipe.next(Ok(None)));
+    /// ```
+    fn transpose_result<T, E>(self) -> TransposeResult<Self>
+    where
+        Self: Sized + Pipe<OutputItem = Result<Option<T>, E>>,
+    {
+        TransposeResult::new(self)
+    }
+
+    /// Cache the output items of a pipe.
+    ///
+    /// The decorated pipe only invokes the original pipe if an input item wasn't seen before and returns a clone of the cached output item otherwise. This is useful for expensive, pure computations that are repeatedly called with the same input items.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Lazy::new(|i: u64| (1..=i).product::<u64>()).memoize();
+    ///
+    /// assert_eq!(120, pipe.next(5));
+    /// assert_eq!(120, pipe.next(5));
+    /// assert_eq!(1, pipe.hits());
+    /// assert_eq!(1, pipe.misses());
+    /// ```
+    fn memoize(self) -> Memoize<Self>
+    where
+        Self: Sized,
+        Self::InputItem: Eq + std::hash::Hash + Clone,
+        Self::OutputItem: Clone,
+    {
+        Memoize::new(self)
+    }
+
+    /// Cache the output items of a pipe in a cache with a bounded capacity.
+    ///
+    /// This decorator behaves like the one created by [`memoize`](#method.memoize), but the cache holds at most `capacity` items. If the cache is full and a new item needs to be cached, the whole cache is cleared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Lazy::new(|i: u64| i * 2).memoize_with_capacity(1);
+    ///
+    /// assert_eq!(2, pipe.next(1));
+    /// assert_eq!(4, pipe.next(2));
+    /// assert_eq!(2, pipe.next(1));
+    /// assert_eq!(3, pipe.misses());
+    /// ```
+    fn memoize_with_capacity(self, capacity: usize) -> Memoize<Self>
+    where
+        Self: Sized,
+        Self::InputItem: Eq + std::hash::Hash + Clone,
+        Self::OutputItem: Clone,
+    {
+        Memoize::with_capacity(self, capacity)
+    }
+
+    /// Collect statistics about the numeric output items of a pipe.
+    ///
+    /// The decorated pipe passes the output items through unchanged, but maintains their count, minimum, maximum, mean and variance, which can be retrieved with [`Metrics::snapshot`](struct.Metrics.html#method.snapshot).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Lazy::new(|i: f32| i * 2.0).metrics();
+    /// for i in 0..5 {
+    ///     pipe.next(i as f32);
+    /// }
+    ///
+    /// let snapshot = pipe.snapshot();
+    /// assert_eq!(5, snapshot.count);
+    /// assert_eq!(0.0, snapshot.min);
+    /// assert_eq!(8.0, snapshot.max);
+    /// assert_eq!(4.0, snapshot.mean);
+    /// ```
+    fn metrics(self) -> Metrics<Self, fn(&Self::OutputItem) -> f64>
+    where
+        Self: Sized,
+        Self::OutputItem: Copy + Into<f64>,
+    {
+        Metrics::new(self, into_f64)
+    }
+
+    /// Collect statistics about projected output items of a pipe.
+    ///
+    /// This decorator behaves like the one created by [`metrics`](#method.metrics), but the statistics are collected about the values returned by the projection function.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Ditto::<&str>::default().metrics_by(|word: &&str| word.len() as f64);
+    /// pipe.next("foo");
+    /// pipe.next("hello");
+    ///
+    /// assert_eq!(4.0, pipe.snapshot().mean);
+    /// ```
+    fn metrics_by<F>(self, projection: F) -> Metrics<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::OutputItem) -> f64,
+    {
+        Metrics::new(self, projection)
+    }
+
+    /// Measure the processing time of a pipe.
+    ///
+    /// The decorated pipe measures the duration of every call to `next` and accumulates the total time, the number of calls and the longest call, which can be retrieved with [`Profile::stats`](struct.Profile.html#method.stats). This can be used to find the slow stage inside a composed pipeline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut stage = Lazy::new(|i: Option<u64>| i.map(|i| i * 2)).profile();
+    /// let mut pipe = PipeIter::new(0..).compose() >> &mut stage;
+    ///
+    /// for _ in 0..16 {
+    ///     pipe.next(());
+    /// }
+    ///
+    /// let stats = stage.stats();
+    /// assert_eq!(16, stats.calls);
+    /// assert_eq!(16, stats.measured_calls);
+    /// assert!(stats.mean().unwrap() <= stats.max);
+    /// ```
+    fn profile(self) -> Profile<Self>
+    where
+        Self: Sized,
+    {
+        Profile::new(self, 1)
+    }
+
+    /// Measure the processing time of some calls of a pipe.
+    ///
+    /// This decorator behaves like the one created by [`profile`](#method.profile), but only every `sample_interval`-th call is measured, which reduces the overhead of the measurement.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Lazy::new(|i: u64| i * 2).profile_sampled(10);
+    /// for i in 0..100 {
+    ///     pipe.next(i);
+    /// }
+    ///
+    /// assert_eq!(100, pipe.stats().calls);
+    /// assert_eq!(10, pipe.stats().measured_calls);
+    /// ```
+    fn profile_sampled(self, sample_interval: usize) -> Profile<Self>
+    where
+        Self: Sized,
+    {
+        Profile::new(self, sample_interval)
+    }
+
+    /// Capture the output items of a pipe.
+    ///
+    /// The decorated pipe clones every output item into a shared buffer and passes it through unchanged. The captured items can be inspected with the returned [`ProbeHandle`](struct.ProbeHandle.html), even if the probe is buried inside a pipeline. This is useful to test intermediate streams of composed pipelines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let (probe, handle) = Lazy::new(|i: u32| i * 2).probe();
+    /// let mut pipe = probe.compose() >> Lazy::new(|i: u32| i + 1);
+    ///
+    /// assert_eq!(5, pipe.next(2));
+    /// assert_eq!(7, pipe.next(3));
+    /// assert_eq!(vec![4, 6], handle.items());
+    /// ```
+    fn probe(self) -> (Probe<Self>, ProbeHandle<Self::OutputItem>)
+    where
+        Self: Sized,
+        Self::OutputItem: Clone,
+    {
+        Probe::new(self, None)
+    }
+
+    /// Capture the latest output items of a pipe.
+    ///
+    /// This decorator behaves like the one created by [`probe`](#method.probe), but only the latest `limit` items are kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let (mut pipe, handle) = Lazy::new(|i: u32| i * 2).probe_bounded(2);
+    /// for i in 0..4 {
+    ///     pipe.next(i);
+    /// }
+    ///
+    /// assert_eq!(vec![4, 6], handle.items());
+    /// ```
+    fn probe_bounded(self, limit: usize) -> (Probe<Self>, ProbeHandle<Self::OutputItem>)
+    where
+        Self: Sized,
+        Self::OutputItem: Clone,
+    {
+        Probe::new(self, Some(limit))
+    }
+
+    /// Limit the rate of a pipe's output items.
+    ///
+    /// The decorated pipe processes every input item, but only returns `Some(item)` if at least `interval` has elapsed since the last passed item and `None` otherwise. The first item passes immediately. If you need a different clock or want to drop the first item, use [`Throttle::with_clock`](struct.Throttle.html#method.with_clock).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    /// use std::time::Duration;
+    ///
+    /// let mut pipe = Counter::new(0u32, 1).throttle(Duration::from_secs(3600));
+    ///
+    /// assert_eq!(Some(0), pipe.next(()));
+    /// assert_eq!(None, pipe.next(()));
+    /// ```
+    fn throttle(self, interval: std::time::Duration) -> Throttle<Self>
+    where
+        Self: Sized,
+    {
+        Throttle::new(self, interval)
+    }
+
+    /// Annotate the output items of a pipe with the time they were produced.
+    ///
+    /// The decorated pipe returns a tuple of the current system time and the output item. If you need a different clock, use [`Timestamp::with_clock`](struct.Timestamp.html#method.with_clock).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Counter::new(0u32, 1).timestamp();
+    ///
+    /// let (first, _) = pipe.next(());
+    /// let (second, _) = pipe.next(());
+    /// assert!(first <= second);
+    /// ```
+    fn timestamp(self) -> Timestamp<Self>
+    where
+        Self: Sized,
+    {
+        Timestamp::new(self)
+    }
+
+    /// Annotate the output items of a pipe with their sample position.
+    ///
+    /// The decorated pipe returns a tuple of the sample position and the output item. The sample position starts at `start` and is incremented with every call. The sample rate is used to convert sample positions to seconds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Counter::new(0u32, 1).sample_stamp(0, 44_100.0);
+    ///
+    /// assert_eq!((0, 0), pipe.next(()));
+    /// assert_eq!((1, 1), pipe.next(()));
+    /// assert_eq!(1.0, pipe.seconds(44_100));
+    /// ```
+    fn sample_stamp(self, start: u64, sample_rate: f64) -> SampleStamp<Self>
+    where
+        Self: Sized,
+    {
+        SampleStamp::new(self, start, sample_rate)
+    }
+
+    /// Only advance a pipe while a gate is open.
+    ///
+    /// The decorated pipe's input item is a tuple of the original input item and a boolean gate. If the gate is open, the original pipe processes the item and its output is returned as `Some`. If the gate is closed, the original pipe is not invoked at all and `None` is returned. Combined with [`unwrap_or`](#method.unwrap_or), a "closed" value like silence can be returned instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Counter::new(0u32, 1).gated().unwrap_or(0);
+    ///
+    /// assert_eq!(0, pipe.next(((), true)));
+    /// assert_eq!(0, pipe.next(((), false)));
+    /// assert_eq!(1, pipe.next(((), true)));
+    /// ```
+    fn gated(self) -> Gated<Self>
+    where
+        Self: Sized,
+    {
+        Gated::new(self)
+    }
+
+    /// Only pass every k-th output item of a pipe.
+    ///
+    /// The decorated pipe still processes every input item, but its output item is only returned as `Some` on the first call and on every `factor`-th call after it. On all other calls, `None` is returned. This way, a pipe running at a lower rate can be connected using the [`optional`](#method.optional) decorator, and an [`Upsample`](struct.Upsample.html) pipe can bring the values back to the original rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Counter::new(0u32, 1).decimate(2);
+    ///
+    /// assert_eq!(Some(0), pipe.next(()));
+    /// assert_eq!(None, pipe.next(()));
+    /// assert_eq!(Some(2), pipe.next(()));
+    /// ```
+    fn decimate(self, factor: usize) -> Decimate<Self>
+    where
+        Self: Sized,
+    {
+        Decimate::new(self, factor)
+    }
+
+    /// Pair every output item of a pipe with its predecessor.
+    ///
+    /// The decorated pipe returns `Some((previous, current))`. Since the first output item has no predecessor, `None` is returned for it. If the first call should return a pair too, use [`pairwise_with`](#method.pairwise_with).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Ditto::<i32>::default().pairwise();
+    ///
+    /// assert_eq!(None, pipe.next(2));
+    /// assert_eq!(Some((2, 5)), pipe.next(5));
+    /// assert_eq!(Some((5, 4)), pipe.next(4));
+    /// ```
+    fn pairwise(self) -> Pairwise<Self>
+    where
+        Self: Sized,
+        Self::OutputItem: Clone,
+    {
+        Pairwise::new(self)
+    }
+
+    /// Pair every output item of a pipe with its predecessor, starting with an initial predecessor.
+    ///
+    /// The decorated pipe returns a tuple of the previous and the current output item. On the first call, `initial` is used as the predecessor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// // Detect rising edges.
+    /// let mut pipe = Ditto::<bool>::default()
+    ///     .pairwise_with(false)
+    ///     .compose()
+    ///     >> Lazy::new(|(previous, current): (bool, bool)| !previous && current);
+    ///
+    /// assert_eq!(true, pipe.next(true));
+    /// assert_eq!(false, pipe.next(true));
+    /// assert_eq!(false, pipe.next(false));
+    /// assert_eq!(true, pipe.next(true));
+    /// ```
+    fn pairwise_with(self, initial: Self::OutputItem) -> PairwiseWith<Self>
+    where
+        Self: Sized,
+        Self::OutputItem: Clone,
+    {
+        PairwiseWith::new(self, initial)
+    }
+
+    /// Calculate a checksum over the output bytes of a pipe.
+    ///
+    /// The decorated pipe passes the output items through unchanged and updates the checksum with their bytes. This is useful to verify the integrity of data that flows through long pipelines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Ditto::<u8>::default().checksum(ChecksumAlgorithm::Crc32);
+    /// for byte in b"123456789" {
+    ///     pipe.next(*byte);
+    /// }
+    /// assert_eq!(0xCBF43926, pipe.value());
+    /// ```
+    fn checksum(self, algorithm: ChecksumAlgorithm) -> Checksum<Self>
+    where
+        Self: Sized,
+        Self::OutputItem: ChecksumBytes,
+    {
+        Checksum::new(self, algorithm)
+    }
+
+    /// Allow a producer pipe to look at its next output item without consuming it.
+    ///
+    /// The decorated pipe has a `peek` method that returns a reference to the next output item, which is then returned by the next call to `next`. This is useful for merging or parsing logic that needs to decide what to do based on the upcoming item.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// const DATA: &[u8] = &[1, 2];
+    ///
+    /// let mut pipe = SliceProducer::new(DATA).peekable();
+    /// assert_eq!(&Some(&1), pipe.peek());
+    /// assert_eq!(Some(&1), pipe.next(()));
+    /// assert_eq!(Some(&2), pipe.next(()));
+    /// assert_eq!(&None, pipe.peek());
+    /// assert_eq!(None, pipe.next(()));
+    /// ```
+    fn peekable(self) -> Peekable<Self>
+    where
+        Self: Sized + Pipe<InputItem = ()>,
+    {
+        Peekable::new(self)
+    }
+
+    /// Convert the output items of a pipe using `From`.
+    ///
+    /// The decorated pipe converts every output item of the original pipe into `U`. Since the decorator has no state of its own, it's a transparent replacement for `Lazy::new(Into::into)` that only needs the target type as an annotation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// const SAMPLES: &[i16] = &[0, 16384, -32768];
+    ///
+    /// let mut pipe = SliceProducer::new(SAMPLES).compose()
+    ///     >> Lazy::new(|i: Option<&i16>| i.cloned().unwrap_or(0)).convert::<f32>()
+    ///     >> Lazy::new(|i: f32| i / 32768.0);
+    ///
+    /// assert_eq!(0.0, pipe.next(()));
+    /// assert_eq!(0.5, pipe.next(()));
+    /// assert_eq!(-1.0, pipe.next(()));
+    /// ```
+    fn convert<U>(self) -> Convert<Self, U>
+    where
+        Self: Sized,
+        U: From<Self::OutputItem>,
+    {
+        Convert::new(self)
+    }
+
+    /// Convert the input items of a pipe using `From`.
+    ///
+    /// The decorated pipe takes input items of type `U` and converts them into the input items of the original pipe.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = Lazy::new(|i: f32| i * 0.5).convert_input::<u8>();
+    /// assert_eq!(2.0, pipe.next(4u8));
+    /// ```
+    fn convert_input<U>(self) -> ConvertInput<Self, U>
+    where
+        Self: Sized,
+        Self::InputItem: From<U>,
+    {
+        ConvertInput::new(self)
+    }
+
+    /// Create a boxed trait object of the pipe.
+    ///
+    /// This might be useful to move pipes across API bounds since it hides the internal composition of the pipe.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// fn create_pipe() -> Box<dyn Pipe<InputItem = usize, OutputItem = usize>> {
+    ///     Lazy::new(|i| i * 2).boxed()
+    /// }
+    ///
+    /// let mut pipe = create_pipe();
+    ///
+    /// for i in 0..4 {
+    ///     assert_eq!(i*2, pipe.next(i));
+    /// }
+    /// ```
+    fn boxed(self) -> Box<dyn Pipe<InputItem = Self::InputItem, OutputItem = Self::OutputItem>>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+/// A pipe that can be reseted to its initial state.
+///
+/// Pipes may have an internal state which they also may modify with every item they process. However, there a situations where wants to reset a pipeline to it's initial state without needing to reconstruct it. Pipes that have the ability to be reseted to their inital state implement this trait. After [`reset`](#method.reset) was called, the pipe is reseted to it's construction state.
+///
+/// Many decorators implement `ResetablePipe` where possible, which means that pipelines that were constructed from resetable pipes will be resetable too. One major exception is the [`LazyMut`](struct.LazyMut.html) pipe. Since the function object may modify it's state and doesn't offer a way to reset this state, a pipeline that contains a `LazyMut` is not resetable.
+pub trait ResetablePipe: Pipe {
+    /// Reset the state of the pipe.
+    ///
+    /// If implemented, this method resets the pipe to the state it had before the first output was retrieved. Since all decorator pipes of this crate implement it, it can be be used to reset the state of a whole pipeline without needing to constructing it again.
+    ///
+    /// If your pipe can't be reseted, you may use the `unimplemented!()` macro. However, you should note this behavior in your documentation!
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// /// A pipe that counts up.
+    /// struct Counter {
+    ///     index: usize,
+    /// }
+    ///
+    /// impl Pipe for Counter {
+    ///     type InputItem = ();
+    ///     type OutputItem = usize;
+    ///
+    ///     fn next(&mut self, _: ()) -> usize {
+    ///         let output = self.index;
+    ///         self.index += 1;
+    ///         output
+    ///     }
+    /// }
+    ///
+    /// impl ResetablePipe for Counter {
+    ///     fn reset(&mut self) {
+    ///         self.index = 0;
+    ///     }
+    /// }
+    ///
+    /// let mut counter = Counter { index: 0};
+    /// assert_eq!(0, counter.next(()));
+    /// assert_eq!(1, counter.next(()));
+    /// counter.reset();
+    /// assert_eq!(0, counter.next(()));
+    /// assert_eq!(1, counter.next(()));
+    /// ```
+    fn reset(&mut self);
+}
+
+impl Pipe for () {
+    type InputItem = ();
+    type OutputItem = ();
+
+    fn next(&mut self, _: ()) {}
+}
+
+impl ResetablePipe for () {
     fn reset(&mut self) {}
 }
 
-impl<'a, P: Pipe + ?Sized> Pipe for &'a mut P {
+impl<P: Pipe + ?Sized> Pipe for &mut P {
     type InputItem = P::InputItem;
     type OutputItem = P::OutputItem;
 
     fn next(&mut self, input: P::InputItem) -> P::OutputItem {
         (*self).next(input)
     }
+
+    fn finish(&mut self) -> Vec<P::OutputItem> {
+        (*self).finish()
+    }
 }
 
-impl<'a, P: ResetablePipe + ?Sized> ResetablePipe for &'a mut P {
+impl<P: ResetablePipe + ?Sized> ResetablePipe for &mut P {
     fn reset(&mut self) {
         (*self).reset();
     }
@@ -555,6 +1474,56 @@ mod composed;
 pub use composed::*;
 
 mod tuples;
+pub use tuples::*;
+
+mod slice;
+pub use slice::*;
+
+mod buffer;
+pub use buffer::*;
+
+mod pull;
+pub use pull::*;
+
+mod text;
+pub use text::*;
+
+mod codec;
+pub use codec::*;
+
+mod result;
+pub use result::*;
+
+mod observe;
+pub use observe::*;
+
+mod aggregate;
+pub use aggregate::*;
+
+mod clock;
+pub use clock::*;
+
+mod mix;
+pub use mix::*;
+
+mod merge;
+pub use merge::*;
+
+mod route;
+pub use route::*;
+
+mod numeric;
+pub use numeric::*;
+
+mod rate;
+pub use rate::*;
+
+mod pipeline;
+pub use pipeline::*;
+
+pub mod dsp;
+
+pub mod testing;
 
 #[test]
 fn trait_object() {
@@ -565,3 +1534,137 @@ fn trait_object() {
         assert_eq!(i, 42);
     }
 }
+
+#[test]
+fn finish_cascade() {
+    /// A pipe that delays items by one call.
+    struct Delay(Option<u32>);
+
+    impl Pipe for Delay {
+        type InputItem = Option<u32>;
+        type OutputItem = Option<u32>;
+
+        fn next(&mut self, item: Option<u32>) -> Option<u32> {
+            std::mem::replace(&mut self.0, item)
+        }
+
+        fn finish(&mut self) -> Vec<Option<u32>> {
+            self.0.take().into_iter().map(Some).collect()
+        }
+    }
+
+    /// A pipe that forwards items and emits their total at the end.
+    struct Total(u32);
+
+    impl Pipe for Total {
+        type InputItem = Option<u32>;
+        type OutputItem = Option<u32>;
+
+        fn next(&mut self, item: Option<u32>) -> Option<u32> {
+            self.0 += item.unwrap_or(0);
+            item
+        }
+
+        fn finish(&mut self) -> Vec<Option<u32>> {
+            vec![Some(self.0)]
+        }
+    }
+
+    impl ResetablePipe for Delay {
+        fn reset(&mut self) {
+            self.0 = None;
+        }
+    }
+
+    let mut pipe = Delay(None).compose() >> Delay(None);
+    assert_eq!(None, pipe.next(Some(1)));
+    assert_eq!(None, pipe.next(Some(2)));
+    assert_eq!(Some(1), pipe.next(Some(3)));
+    assert_eq!(vec![Some(2), Some(3)], pipe.finish());
+    assert!(pipe.finish().is_empty());
+
+    let pipe = PipeIter::new(1..=3).compose() >> Total(0) >> Total(0);
+    let items: Vec<u32> = pipe.into_iter().collect();
+    assert_eq!(vec![1, 2, 3, 6, 12], items);
+
+    let mut chain = DynChain::new();
+    chain.push_stage(Delay(None));
+    chain.push_stage(Lazy::new(|i: Option<u32>| i.map(|i| i * 10)));
+    chain.push_stage(Delay(None));
+    assert_eq!(None, chain.next(Some(1)));
+    assert_eq!(None, chain.next(Some(2)));
+    assert_eq!(vec![Some(10), Some(20)], chain.finish());
+}
+
+#[test]
+fn finish_decorators() {
+    /// A pipe that holds back all items until it's finished.
+    struct Hold<T>(Vec<T>);
+
+    impl<T> Pipe for Hold<T> {
+        type InputItem = Option<T>;
+        type OutputItem = Option<T>;
+
+        fn next(&mut self, item: Option<T>) -> Option<T> {
+            self.0.extend(item);
+            None
+        }
+
+        fn finish(&mut self) -> Vec<Option<T>> {
+            self.0.drain(..).map(Some).collect()
+        }
+    }
+
+    let mut pipe = Hold(Vec::new()).unwrap_or(0);
+    assert_eq!(0, pipe.next(Some(1)));
+    assert_eq!(0, pipe.next(Some(2)));
+    assert_eq!(0, pipe.next(Some(3)));
+    assert_eq!(vec![1, 2, 3], pipe.finish());
+
+    let mut pipe = Hold(Vec::new()).ok_or("empty").map_err(|e: &str| e.len());
+    assert_eq!(Err(5), pipe.next(Some(1)));
+    assert_eq!(Err(5), pipe.next(Some(2)));
+    assert_eq!(vec![Ok(1), Ok(2)], pipe.finish());
+
+    let mut pipe = Hold(Vec::new()).decimate(2).enumerate();
+    for i in 1..=4 {
+        pipe.next(Some(i));
+    }
+    assert_eq!(
+        vec![(4, Some(Some(1))), (5, None), (6, Some(Some(3))), (7, None)],
+        pipe.finish()
+    );
+
+    let mut pipe = Hold(Vec::new()).sample_stamp(10, 1.0);
+    pipe.next(Some(1));
+    pipe.next(Some(2));
+    assert_eq!(vec![(12, Some(1)), (13, Some(2))], pipe.finish());
+
+    let (mut pipe, handle) = Hold(Vec::new()).unwrap_or_default().pairwise().probe();
+    pipe.next(Some(1u8));
+    pipe.next(Some(2u8));
+    assert_eq!(vec![Some((0, 1)), Some((1, 2))], pipe.finish());
+    assert_eq!(
+        vec![None, Some((0, 0)), Some((0, 1)), Some((1, 2))],
+        handle.items()
+    );
+
+    let mut pipe = (
+        Hold(Vec::new()),
+        Hold(Vec::new()).metrics_by(|_: &Option<u8>| 1.0),
+    );
+    pipe.next((Some(1), Some(4)));
+    pipe.next((Some(2), Some(5)));
+    assert_eq!(vec![(Some(1), Some(4)), (Some(2), Some(5))], pipe.finish());
+    assert_eq!(4, pipe.1.snapshot().count);
+
+    // The surplus items of the holding pipe are dropped.
+    let mut pipe = (Hold(Vec::new()), Ditto::<u8>::default());
+    pipe.next((Some(1), 1));
+    assert!(pipe.finish().is_empty());
+
+    // There are no input items to pair the remaining items with.
+    let mut pipe = Hold(Vec::new()).bypass();
+    assert_eq!((Some(1), None), pipe.next(Some(1)));
+    assert!(pipe.finish().is_empty());
+}
@@ -0,0 +1,231 @@
+use crate::{Pipe, ResetablePipe};
+use std::cmp::Ordering;
+
+/// A pipe that merges the output items of two sorted producer pipes into one sorted stream.
+///
+/// Both pipes have to yield their items in ascending order, as defined by the ordering function, and signal the end of their stream with `None`. The merger holds one lookahead item per side: Every call to `next` polls the sides whose lookahead is empty, compares both lookahead items and returns the smaller one. The side of the returned item is refilled on the next call. If both items are equal, the item of `A` comes first, so the merge is stable.
+///
+/// Once a side has returned `None`, it's considered to be exhausted and isn't polled again, so the pipes don't need to be fused. The remaining items of the other side are then passed through, and once both sides are exhausted, `None` is returned. Resetting this pipe resets both pipes and drops the lookahead items.
+///
+/// Finishing this pipe finishes both pipes and merges their remaining items, including the lookahead items, the same way. `None` items among the remaining items are skipped.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let left: &[u8] = &[1, 4, 5];
+/// let right: &[u8] = &[2, 3, 6, 7];
+/// let mut merger = MergeBy::new(
+///     SliceProducer::new(left),
+///     SliceProducer::new(right),
+///     |a: &&u8, b: &&u8| a.cmp(b),
+/// );
+///
+/// let merged: Vec<u8> = (0..8).filter_map(|_| merger.next(())).cloned().collect();
+/// assert_eq!(vec![1, 2, 3, 4, 5, 6, 7], merged);
+/// assert_eq!(None, merger.next(()));
+/// ```
+pub struct MergeBy<A, B, T, F>
+where
+    A: Pipe<InputItem = (), OutputItem = Option<T>>,
+    B: Pipe<InputItem = (), OutputItem = Option<T>>,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    a: A,
+    b: B,
+    compare: F,
+    a_item: Option<T>,
+    b_item: Option<T>,
+    a_ended: bool,
+    b_ended: bool,
+}
+
+impl<A, B, T, F> MergeBy<A, B, T, F>
+where
+    A: Pipe<InputItem = (), OutputItem = Option<T>>,
+    B: Pipe<InputItem = (), OutputItem = Option<T>>,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    /// Create a new merger of the two pipes with the given ordering function.
+    pub fn new(a: A, b: B, compare: F) -> Self {
+        Self {
+            a,
+            b,
+            compare,
+            a_item: None,
+            b_item: None,
+            a_ended: false,
+            b_ended: false,
+        }
+    }
+
+    /// Poll a side if its lookahead is empty and it's not exhausted yet.
+    fn refill<P>(pipe: &mut P, item: &mut Option<T>, ended: &mut bool)
+    where
+        P: Pipe<InputItem = (), OutputItem = Option<T>>,
+    {
+        if item.is_none() && !*ended {
+            *item = pipe.next(());
+            *ended = item.is_none();
+        }
+    }
+}
+
+impl<A, B, T, F> Pipe for MergeBy<A, B, T, F>
+where
+    A: Pipe<InputItem = (), OutputItem = Option<T>>,
+    B: Pipe<InputItem = (), OutputItem = Option<T>>,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    type InputItem = ();
+    type OutputItem = Option<T>;
+
+    fn next(&mut self, _: ()) -> Option<T> {
+        Self::refill(&mut self.a, &mut self.a_item, &mut self.a_ended);
+        Self::refill(&mut self.b, &mut self.b_item, &mut self.b_ended);
+
+        let take_a = match (&self.a_item, &self.b_item) {
+            (Some(a), Some(b)) => (self.compare)(a, b) != Ordering::Greater,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if take_a {
+            self.a_item.take()
+        } else {
+            self.b_item.take()
+        }
+    }
+
+    fn finish(&mut self) -> Vec<Option<T>> {
+        let mut a = self
+            .a_item
+            .take()
+            .into_iter()
+            .chain(self.a.finish().into_iter().flatten())
+            .peekable();
+        let mut b = self
+            .b_item
+            .take()
+            .into_iter()
+            .chain(self.b.finish().into_iter().flatten())
+            .peekable();
+        self.a_ended = true;
+        self.b_ended = true;
+
+        let mut items = Vec::new();
+        loop {
+            let take_a = match (a.peek(), b.peek()) {
+                (Some(a), Some(b)) => (self.compare)(a, b) != Ordering::Greater,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => return items,
+            };
+            items.push(if take_a { a.next() } else { b.next() });
+        }
+    }
+}
+
+impl<A, B, T, F> ResetablePipe for MergeBy<A, B, T, F>
+where
+    A: ResetablePipe<InputItem = (), OutputItem = Option<T>>,
+    B: ResetablePipe<InputItem = (), OutputItem = Option<T>>,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    fn reset(&mut self) {
+        self.a.reset();
+        self.b.reset();
+        self.a_item = None;
+        self.b_item = None;
+        self.a_ended = false;
+        self.b_ended = false;
+    }
+}
+
+#[test]
+fn merge_by_sorted_random() {
+    use crate::dsp::XorShift;
+    use crate::SliceProducer;
+
+    let mut rng = XorShift::new(0x5eed);
+    for _ in 0..64 {
+        let mut left: Vec<u8> = (0..rng.next_u64() % 32)
+            .map(|_| rng.next_u64() as u8)
+            .collect();
+        let mut right: Vec<u8> = (0..rng.next_u64() % 32)
+            .map(|_| rng.next_u64() as u8)
+            .collect();
+        left.sort_unstable();
+        right.sort_unstable();
+
+        let mut expected: Vec<u8> = left.iter().chain(right.iter()).cloned().collect();
+        expected.sort_unstable();
+
+        let mut merger = MergeBy::new(
+            SliceProducer::new(&left),
+            SliceProducer::new(&right),
+            |a: &&u8, b: &&u8| a.cmp(b),
+        );
+        for _ in 0..2 {
+            let merged: Vec<u8> = (0..expected.len())
+                .map(|_| *merger.next(()).unwrap())
+                .collect();
+            assert_eq!(expected, merged);
+            assert_eq!(None, merger.next(()));
+            assert_eq!(None, merger.next(()));
+            merger.reset();
+        }
+    }
+}
+
+#[test]
+fn merge_by_stable_and_not_fused() {
+    use crate::testing::MockPipe;
+
+    // The mocks panic if they are polled after their end.
+    let a: MockPipe<(), Option<(u8, char)>> =
+        MockPipe::new(vec![Some((1, 'a')), Some((2, 'a')), None]);
+    let b: MockPipe<(), Option<(u8, char)>> = MockPipe::new(vec![Some((1, 'b')), None]);
+    let mut merger = MergeBy::new(a, b, |x: &(u8, char), y: &(u8, char)| x.0.cmp(&y.0));
+
+    assert_eq!(Some((1, 'a')), merger.next(()));
+    assert_eq!(Some((1, 'b')), merger.next(()));
+    assert_eq!(Some((2, 'a')), merger.next(()));
+    assert_eq!(None, merger.next(()));
+    assert_eq!(None, merger.next(()));
+
+    merger.reset();
+    assert_eq!(Some((1, 'a')), merger.next(()));
+}
+
+#[test]
+fn merge_by_finish() {
+    /// A sorted producer that returns all items it didn't yield yet when it's finished.
+    struct Source(std::vec::IntoIter<u8>);
+
+    impl Pipe for Source {
+        type InputItem = ();
+        type OutputItem = Option<u8>;
+
+        fn next(&mut self, _: ()) -> Option<u8> {
+            self.0.next()
+        }
+
+        fn finish(&mut self) -> Vec<Option<u8>> {
+            self.0.by_ref().map(Some).collect()
+        }
+    }
+
+    let a = Source(vec![2, 6, 7].into_iter());
+    let b = Source(vec![1, 3, 4, 8].into_iter());
+    let mut merger = MergeBy::new(a, b, |x: &u8, y: &u8| x.cmp(y));
+    assert_eq!(Some(1), merger.next(()));
+    assert_eq!(Some(2), merger.next(()));
+    // The lookahead item `3` is merged with the remaining items too.
+    assert_eq!(
+        vec![Some(3), Some(4), Some(6), Some(7), Some(8)],
+        merger.finish()
+    );
+    assert!(merger.finish().is_empty());
+    assert_eq!(None, merger.next(()));
+}
@@ -0,0 +1,347 @@
+use crate::{Pipe, ResetablePipe};
+
+/// A pipe that blends the outputs of two pipes.
+///
+/// Both pipes receive a clone of the same input item and are always advanced, so their state stays continuous. Their outputs are blended using a mix position `x` in `[0.0, 1.0]`: The output is `a * (1 - x) + b * x`, so a mix position of `0.0` returns the output of `A` and a mix position of `1.0` returns the output of `B`.
+///
+/// The mix position can be changed with [`set_target`](#method.set_target). In order to avoid clicks, the mix position glides linearly to the target over a given number of calls. Resetting this pipe resets both pipes and snaps the mix position to its initial value. When this pipe is finished, the remaining items of both pipes are blended pairwise, and the surplus items of the pipe with more remaining items are dropped.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipe = Crossfade::new(Const::new(1.0), Const::new(-1.0), 0.0);
+/// assert_eq!(1.0, pipe.next(()));
+///
+/// pipe.set_target(1.0, 2);
+/// assert_eq!(0.0, pipe.next(()));
+/// assert_eq!(-1.0, pipe.next(()));
+/// assert_eq!(-1.0, pipe.next(()));
+/// ```
+pub struct Crossfade<A, B>
+where
+    A: Pipe<OutputItem = f32>,
+    B: Pipe<InputItem = A::InputItem, OutputItem = f32>,
+    A::InputItem: Clone,
+{
+    a: A,
+    b: B,
+    initial_mix: f32,
+    mix: f32,
+    target: f32,
+    step: f32,
+    remaining: usize,
+}
+
+impl<A, B> Crossfade<A, B>
+where
+    A: Pipe<OutputItem = f32>,
+    B: Pipe<InputItem = A::InputItem, OutputItem = f32>,
+    A::InputItem: Clone,
+{
+    /// Create a new crossfade with the given initial mix position.
+    ///
+    /// The mix position is clamped to `[0.0, 1.0]`.
+    pub fn new(a: A, b: B, initial_mix: f32) -> Self {
+        let initial_mix = initial_mix.clamp(0.0, 1.0);
+        Self {
+            a,
+            b,
+            initial_mix,
+            mix: initial_mix,
+            target: initial_mix,
+            step: 0.0,
+            remaining: 0,
+        }
+    }
+
+    /// Return the current mix position.
+    pub fn mix(&self) -> f32 {
+        self.mix
+    }
+
+    /// Glide the mix position to the target over `ramp_len` calls.
+    ///
+    /// The target is clamped to `[0.0, 1.0]`. After `ramp_len` calls, the mix position is exactly the target. If `ramp_len` is zero, the mix position jumps to the target immediately.
+    pub fn set_target(&mut self, target: f32, ramp_len: usize) {
+        let target = target.clamp(0.0, 1.0);
+        self.target = target;
+        if ramp_len == 0 {
+            self.mix = target;
+            self.remaining = 0;
+        } else {
+            self.step = (target - self.mix) / ramp_len as f32;
+            self.remaining = ramp_len;
+        }
+    }
+
+    /// Advance the mix position and blend the two output items.
+    fn blend(&mut self, a: f32, b: f32) -> f32 {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            if self.remaining == 0 {
+                self.mix = self.target;
+            } else {
+                self.mix += self.step;
+            }
+        }
+        a * (1.0 - self.mix) + b * self.mix
+    }
+}
+
+impl<A, B> Pipe for Crossfade<A, B>
+where
+    A: Pipe<OutputItem = f32>,
+    B: Pipe<InputItem = A::InputItem, OutputItem = f32>,
+    A::InputItem: Clone,
+{
+    type InputItem = A::InputItem;
+    type OutputItem = f32;
+
+    fn next(&mut self, item: A::InputItem) -> f32 {
+        let a = self.a.next(item.clone());
+        let b = self.b.next(item);
+        self.blend(a, b)
+    }
+
+    fn finish(&mut self) -> Vec<f32> {
+        let b = self.b.finish();
+        self.a
+            .finish()
+            .into_iter()
+            .zip(b)
+            .map(|(a, b)| self.blend(a, b))
+            .collect()
+    }
+}
+
+impl<A, B> ResetablePipe for Crossfade<A, B>
+where
+    A: ResetablePipe<OutputItem = f32>,
+    B: ResetablePipe<InputItem = A::InputItem, OutputItem = f32>,
+    A::InputItem: Clone,
+{
+    fn reset(&mut self) {
+        self.a.reset();
+        self.b.reset();
+        self.mix = self.initial_mix;
+        self.target = self.initial_mix;
+        self.remaining = 0;
+    }
+}
+
+/// A boxed source of a [`Mixer`](struct.Mixer.html).
+pub type MixerSource = Box<dyn ResetablePipe<InputItem = (), OutputItem = f32> + Send>;
+
+/// A pipe that sums the outputs of an arbitrary number of producer pipes.
+///
+/// Every call to `next` advances every source once and returns the sum of their outputs, each multiplied with the source's gain. An empty mixer returns zero. Since the sources are boxed, they can be added and removed at runtime, for example to implement the voices of a polyphonic synthesizer. Resetting the mixer resets all sources. Finishing the mixer finishes all sources and sums their remaining items the same way, where sources with fewer remaining items only contribute to the first items.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut mixer = Mixer::new();
+/// assert_eq!(0.0, mixer.next(()));
+///
+/// mixer.add_source(Const::new(1.0));
+/// mixer.add_source_with_gain(Const::new(2.0), 0.5);
+/// assert_eq!(2.0, mixer.next(()));
+/// ```
+#[derive(Default)]
+pub struct Mixer {
+    sources: Vec<(MixerSource, f32)>,
+}
+
+impl Mixer {
+    /// Create a new, empty mixer.
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+        }
+    }
+
+    /// Add a source with a gain of 1.0 and return its index.
+    pub fn add_source<P>(&mut self, source: P) -> usize
+    where
+        P: ResetablePipe<InputItem = (), OutputItem = f32> + Send + 'static,
+    {
+        self.add_source_with_gain(source, 1.0)
+    }
+
+    /// Add a source with the given gain and return its index.
+    pub fn add_source_with_gain<P>(&mut self, source: P, gain: f32) -> usize
+    where
+        P: ResetablePipe<InputItem = (), OutputItem = f32> + Send + 'static,
+    {
+        self.sources.push((Box::new(source), gain));
+        self.sources.len() - 1
+    }
+
+    /// Return the gain of the source with the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn gain(&self, index: usize) -> f32 {
+        self.sources[index].1
+    }
+
+    /// Set the gain of the source with the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_gain(&mut self, index: usize, gain: f32) {
+        self.sources[index].1 = gain;
+    }
+
+    /// Remove the source with the given index and return it.
+    ///
+    /// The indices of all following sources are shifted down by one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> MixerSource {
+        self.sources.remove(index).0
+    }
+
+    /// Return the number of sources.
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Return `true` if the mixer has no sources.
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+}
+
+impl Pipe for Mixer {
+    type InputItem = ();
+    type OutputItem = f32;
+
+    fn next(&mut self, _: ()) -> f32 {
+        self.sources
+            .iter_mut()
+            .map(|(source, gain)| source.next(()) * *gain)
+            .sum()
+    }
+
+    fn finish(&mut self) -> Vec<f32> {
+        let mut items: Vec<f32> = Vec::new();
+        for (source, gain) in self.sources.iter_mut() {
+            let tail = source.finish();
+            if items.len() < tail.len() {
+                items.resize(tail.len(), 0.0);
+            }
+            for (sum, item) in items.iter_mut().zip(tail) {
+                *sum += item * *gain;
+            }
+        }
+        items
+    }
+}
+
+impl ResetablePipe for Mixer {
+    fn reset(&mut self) {
+        for (source, _) in self.sources.iter_mut() {
+            source.reset();
+        }
+    }
+}
+
+#[test]
+fn crossfade_ramp() {
+    use crate::{Const, Counter};
+
+    let mut pipe = Crossfade::new(Counter::new(0.0, 1.0), Const::new(100.0), 0.0);
+    assert_eq!(0.0, pipe.next(()));
+    assert_eq!(1.0, pipe.next(()));
+
+    pipe.set_target(1.0, 4);
+    let mixes: Vec<f32> = (0..5)
+        .map(|_| {
+            pipe.next(());
+            pipe.mix()
+        })
+        .collect();
+    assert_eq!(vec![0.25, 0.5, 0.75, 1.0, 1.0], mixes);
+    // Pure B, but A was still advanced.
+    assert_eq!(100.0, pipe.next(()));
+
+    pipe.set_target(0.0, 0);
+    assert_eq!(8.0, pipe.next(()));
+
+    pipe.set_target(0.5, 2);
+    pipe.reset();
+    assert_eq!(0.0, pipe.next(()));
+    assert_eq!(0.0, pipe.mix());
+}
+
+#[test]
+fn mixer() {
+    use crate::{Const, Counter};
+
+    let mut mixer = Mixer::new();
+    assert!(mixer.is_empty());
+    mixer.add_source(Counter::new(0.0, 1.0));
+    mixer.add_source_with_gain(Counter::new(10.0, -2.0), 0.5);
+    mixer.add_source(Const::new(0.25));
+    assert_eq!(3, mixer.len());
+
+    for i in 0..4 {
+        let expected = i as f32 + (10.0 - 2.0 * i as f32) * 0.5 + 0.25;
+        assert_eq!(expected, mixer.next(()));
+    }
+
+    mixer.remove(1);
+    assert_eq!(2, mixer.len());
+    assert_eq!(4.25, mixer.next(()));
+
+    mixer.set_gain(0, 2.0);
+    assert_eq!(2.0, mixer.gain(0));
+    assert_eq!(10.25, mixer.next(()));
+
+    mixer.reset();
+    assert_eq!(0.25, mixer.next(()));
+
+    let mut pipe: Box<dyn Pipe<InputItem = (), OutputItem = f32> + Send> = Box::new(mixer);
+    assert_eq!(2.25, pipe.next(()));
+}
+
+#[test]
+fn mixer_finish() {
+    use crate::Const;
+
+    /// A source that returns its remaining levels when it's finished.
+    struct Tail(Vec<f32>);
+
+    impl Pipe for Tail {
+        type InputItem = ();
+        type OutputItem = f32;
+
+        fn next(&mut self, _: ()) -> f32 {
+            0.0
+        }
+
+        fn finish(&mut self) -> Vec<f32> {
+            std::mem::take(&mut self.0)
+        }
+    }
+
+    impl ResetablePipe for Tail {
+        fn reset(&mut self) {}
+    }
+
+    let mut mixer = Mixer::new();
+    mixer.add_source(Tail(vec![1.0, 2.0]));
+    mixer.add_source_with_gain(Tail(vec![4.0, 8.0, 16.0]), 0.5);
+    mixer.add_source(Const::new(1.0));
+    assert_eq!(1.0, mixer.next(()));
+    assert_eq!(vec![3.0, 6.0, 8.0], mixer.finish());
+    assert!(mixer.finish().is_empty());
+}
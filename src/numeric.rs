@@ -0,0 +1,1364 @@
+use crate::dsp::XorShift;
+use crate::{Interpolate, Pipe, ResetablePipe};
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Sub};
+
+/// The way a [`Smoother`](struct.Smoother.html) moves towards its target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmoothingMode {
+    /// Move towards the target by at most `max_delta` per call.
+    Linear { max_delta: f32 },
+    /// Move towards the target by a fixed fraction of the remaining distance per call.
+    ///
+    /// The fraction is derived from the time constant: After `time_constant` calls, about 63% of the distance to a constant target is covered.
+    Exponential { time_constant: f32 },
+}
+
+/// A pipe that smoothes abrupt changes of a control value.
+///
+/// The input item of this pipe is the target value and the output item is the current value, which moves towards the target with every call. Depending on the [`SmoothingMode`](enum.SmoothingMode.html), this is either a linear ramp or a one-pole (exponential) glide.
+///
+/// Once the current value is close enough to the target, it snaps to the target exactly: In linear mode, this happens as soon as the distance is less than or equal to `max_delta` and in exponential mode, this happens as soon as the distance is less than `1e-6` or a step doesn't change the value anymore. This way, the value never gets stuck just next to the target due to floating-point errors.
+///
+/// Resetting this pipe restores the initial value.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut smoother = Smoother::linear(0.0, 0.25);
+/// assert_eq!(0.25, smoother.next(1.0));
+/// assert_eq!(0.5, smoother.next(1.0));
+/// assert_eq!(0.75, smoother.next(1.0));
+/// assert_eq!(1.0, smoother.next(1.0));
+/// assert_eq!(1.0, smoother.next(1.0));
+/// ```
+pub struct Smoother {
+    initial_value: f32,
+    value: f32,
+    mode: SmoothingMode,
+    coefficient: f32,
+}
+
+impl Smoother {
+    /// The distance at which the exponential mode snaps to the target.
+    const SNAP_DISTANCE: f32 = 1e-6;
+
+    /// Create a new smoother with the given initial value and mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mode is linear and `max_delta` is not positive and finite.
+    pub fn new(initial_value: f32, mode: SmoothingMode) -> Self {
+        let coefficient = match mode {
+            SmoothingMode::Linear { max_delta } => {
+                assert!(
+                    max_delta > 0.0 && max_delta.is_finite(),
+                    "The maximal delta of a smoother must be positive and finite"
+                );
+                0.0
+            }
+            SmoothingMode::Exponential { time_constant } => {
+                1.0 - (-1.0 / time_constant.max(f32::MIN_POSITIVE)).exp()
+            }
+        };
+        Self {
+            initial_value,
+            value: initial_value,
+            mode,
+            coefficient,
+        }
+    }
+
+    /// Create a new linear smoother that moves by at most `max_delta` per call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_delta` is not positive and finite.
+    pub fn linear(initial_value: f32, max_delta: f32) -> Self {
+        Self::new(initial_value, SmoothingMode::Linear { max_delta })
+    }
+
+    /// Create a new exponential smoother with the given time constant in calls.
+    pub fn exponential(initial_value: f32, time_constant: f32) -> Self {
+        Self::new(initial_value, SmoothingMode::Exponential { time_constant })
+    }
+
+    /// Return the current value.
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+}
+
+impl Pipe for Smoother {
+    type InputItem = f32;
+    type OutputItem = f32;
+
+    fn next(&mut self, target: f32) -> f32 {
+        let distance = target - self.value;
+        self.value = match self.mode {
+            SmoothingMode::Linear { max_delta } => {
+                if distance.abs() <= max_delta {
+                    target
+                } else {
+                    self.value + max_delta.copysign(distance)
+                }
+            }
+            SmoothingMode::Exponential { .. } => {
+                let value = self.value + distance * self.coefficient;
+                if (target - value).abs() < Self::SNAP_DISTANCE || value == self.value {
+                    target
+                } else {
+                    value
+                }
+            }
+        };
+        self.value
+    }
+}
+
+impl ResetablePipe for Smoother {
+    fn reset(&mut self) {
+        self.value = self.initial_value;
+    }
+}
+
+/// A pipe that calculates the difference between consecutive input items.
+///
+/// Every call returns `current - previous`, which is the discrete derivative of the input. For the first call, the predecessor is either a given initial value, so that an initial value of zero returns the raw first item, or the first item itself, so that zero is returned. The [`RunningSum`](struct.RunningSum.html) is the inverse of this pipe.
+///
+/// Integer types follow Rust's usual overflow semantics: An overflow panics in debug builds and wraps in release builds. If wrapping is intended, use [`Wrapping`](https://doc.rust-lang.org/std/num/struct.Wrapping.html) integers.
+///
+/// Resetting this pipe restores the initial predecessor.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut raw = Difference::new(0);
+/// assert_eq!(3, raw.next(3));
+/// assert_eq!(2, raw.next(5));
+/// assert_eq!(-1, raw.next(4));
+///
+/// let mut zero = Difference::zero_first();
+/// assert_eq!(0, zero.next(3));
+/// assert_eq!(2, zero.next(5));
+/// ```
+pub struct Difference<T>
+where
+    T: Sub<Output = T> + Copy,
+{
+    initial: Option<T>,
+    previous: Option<T>,
+}
+
+impl<T> Difference<T>
+where
+    T: Sub<Output = T> + Copy,
+{
+    /// Create a new difference pipe that uses the given initial value as the predecessor of the first item.
+    pub fn new(initial: T) -> Self {
+        Self {
+            initial: Some(initial),
+            previous: Some(initial),
+        }
+    }
+
+    /// Create a new difference pipe that returns zero for the first item.
+    pub fn zero_first() -> Self {
+        Self {
+            initial: None,
+            previous: None,
+        }
+    }
+}
+
+impl<T> Pipe for Difference<T>
+where
+    T: Sub<Output = T> + Copy,
+{
+    type InputItem = T;
+    type OutputItem = T;
+
+    fn next(&mut self, item: T) -> T {
+        let previous = self.previous.unwrap_or(item);
+        self.previous = Some(item);
+        item - previous
+    }
+}
+
+impl<T> ResetablePipe for Difference<T>
+where
+    T: Sub<Output = T> + Copy,
+{
+    fn reset(&mut self) {
+        self.previous = self.initial;
+    }
+}
+
+/// A pipe that calculates the cumulative sum of all input items so far.
+///
+/// Every call adds the input item to the sum and returns the new sum, which is the discrete integral of the input. The sum starts with a given initial value. This pipe is the inverse of the [`Difference`](struct.Difference.html) pipe.
+///
+/// Integer types follow Rust's usual overflow semantics: An overflow panics in debug builds and wraps in release builds. If wrapping is intended, use [`Wrapping`](https://doc.rust-lang.org/std/num/struct.Wrapping.html) integers.
+///
+/// Resetting this pipe restores the initial value.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut sum = RunningSum::new(0);
+/// assert_eq!(3, sum.next(3));
+/// assert_eq!(8, sum.next(5));
+/// assert_eq!(7, sum.next(-1));
+/// ```
+pub struct RunningSum<T>
+where
+    T: Add<Output = T> + Copy,
+{
+    initial: T,
+    sum: T,
+}
+
+impl<T> RunningSum<T>
+where
+    T: Add<Output = T> + Copy,
+{
+    /// Create a new running sum that starts with the given value.
+    pub fn new(initial: T) -> Self {
+        Self {
+            initial,
+            sum: initial,
+        }
+    }
+
+    /// Return the current sum.
+    pub fn sum(&self) -> T {
+        self.sum
+    }
+}
+
+impl<T> Pipe for RunningSum<T>
+where
+    T: Add<Output = T> + Copy,
+{
+    type InputItem = T;
+    type OutputItem = T;
+
+    fn next(&mut self, item: T) -> T {
+        self.sum = self.sum + item;
+        self.sum
+    }
+}
+
+impl<T> ResetablePipe for RunningSum<T>
+where
+    T: Add<Output = T> + Copy,
+{
+    fn reset(&mut self) {
+        self.sum = self.initial;
+    }
+}
+
+/// The way a [`Clamp`](struct.Clamp.html) pipe handles values that can't be compared, like `NaN`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// Pass the value through unchanged.
+    Propagate,
+    /// Replace the value with the minimum.
+    ClampToMin,
+}
+
+/// A pipe that limits its input items to a range.
+///
+/// Items that are less than the minimum are replaced by the minimum and items that are greater than the maximum are replaced by the maximum. Items that aren't comparable with themselves, like `NaN`, are handled according to the [`NanPolicy`](enum.NanPolicy.html), which propagates them by default.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipe = Lazy::new(|x: f32| x * 4.0).compose() >> Clamp::new(-1.0, 1.0);
+/// assert_eq!(0.5, pipe.next(0.125));
+/// assert_eq!(1.0, pipe.next(0.5));
+/// assert_eq!(-1.0, pipe.next(-0.5));
+/// assert!(pipe.next(f32::NAN).is_nan());
+///
+/// let mut pipe = Clamp::new(-1.0, 1.0).with_nan_policy(NanPolicy::ClampToMin);
+/// assert_eq!(-1.0, pipe.next(f32::NAN));
+/// ```
+pub struct Clamp<T: PartialOrd + Copy> {
+    min: T,
+    max: T,
+    nan_policy: NanPolicy,
+}
+
+impl<T: PartialOrd + Copy> Clamp<T> {
+    /// Create a new clamping pipe with the given range that propagates `NaN`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min` is greater than `max` or if they can't be compared.
+    pub fn new(min: T, max: T) -> Self {
+        assert!(
+            min <= max,
+            "The minimum of a clamp must be less than or equal to its maximum"
+        );
+        Self {
+            min,
+            max,
+            nan_policy: NanPolicy::Propagate,
+        }
+    }
+
+    /// Set the way incomparable values are handled.
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+}
+
+impl<T: PartialOrd + Copy> Pipe for Clamp<T> {
+    type InputItem = T;
+    type OutputItem = T;
+
+    fn next(&mut self, item: T) -> T {
+        if item.partial_cmp(&item).is_none() {
+            match self.nan_policy {
+                NanPolicy::Propagate => item,
+                NanPolicy::ClampToMin => self.min,
+            }
+        } else if item < self.min {
+            self.min
+        } else if item > self.max {
+            self.max
+        } else {
+            item
+        }
+    }
+}
+
+impl<T: PartialOrd + Copy> ResetablePipe for Clamp<T> {
+    fn reset(&mut self) {}
+}
+
+/// The transfer curve of a [`Saturate`](struct.Saturate.html) pipe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaturationCurve {
+    /// The hyperbolic tangent, which approaches `-1.0` and `1.0` asymptotically.
+    Tanh,
+    /// A cubic polynomial, which reaches `-1.0` and `1.0` at inputs of `-1.0` and `1.0` and is constant beyond them.
+    Cubic,
+}
+
+/// A pipe that softly limits its input items to `[-1.0, 1.0]`.
+///
+/// In contrast to a hard [`Clamp`](struct.Clamp.html), the transfer curve bends smoothly towards its limits, which avoids the harsh distortion of digital clipping. Both curves are odd, monotonic and nearly linear around zero, so quiet signals are barely distorted. `NaN` is propagated.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipe = Lazy::new(|x: f32| x * 8.0).compose() >> Saturate::cubic();
+/// assert_eq!(0.0, pipe.next(0.0));
+/// assert_eq!(1.0, pipe.next(1.0));
+/// assert_eq!(-1.0, pipe.next(-1.0));
+///
+/// let mut pipe = Lazy::new(|x: f32| x * 8.0).compose() >> Saturate::tanh();
+/// let value = pipe.next(1.0);
+/// assert!(0.99 < value && value <= 1.0);
+/// ```
+pub struct Saturate {
+    curve: SaturationCurve,
+}
+
+impl Saturate {
+    /// Create a new saturation pipe with the given curve.
+    pub fn new(curve: SaturationCurve) -> Self {
+        Self { curve }
+    }
+
+    /// Create a new saturation pipe with the hyperbolic tangent curve.
+    pub fn tanh() -> Self {
+        Self::new(SaturationCurve::Tanh)
+    }
+
+    /// Create a new saturation pipe with the cubic curve.
+    pub fn cubic() -> Self {
+        Self::new(SaturationCurve::Cubic)
+    }
+}
+
+impl Pipe for Saturate {
+    type InputItem = f32;
+    type OutputItem = f32;
+
+    fn next(&mut self, item: f32) -> f32 {
+        match self.curve {
+            SaturationCurve::Tanh => item.tanh(),
+            SaturationCurve::Cubic => {
+                if item >= 1.0 {
+                    1.0
+                } else if item <= -1.0 {
+                    -1.0
+                } else {
+                    1.5 * (item - item * item * item / 3.0)
+                }
+            }
+        }
+    }
+}
+
+impl ResetablePipe for Saturate {
+    fn reset(&mut self) {}
+}
+
+/// A parameter value that may glide linearly to a new target.
+#[derive(Debug, Clone, Copy)]
+struct Ramp<T: Copy> {
+    start: T,
+    target: T,
+    value: T,
+    position: usize,
+}
+
+impl<T: Copy> Ramp<T> {
+    fn new(value: T) -> Self {
+        Self {
+            start: value,
+            target: value,
+            value,
+            position: 0,
+        }
+    }
+
+    fn set(&mut self, target: T, length: usize, interpolate: Option<fn(&T, &T, f32) -> T>) {
+        self.start = self.value;
+        self.target = target;
+        self.position = 0;
+        if interpolate.is_none() || length == 0 {
+            self.value = target;
+        }
+    }
+
+    fn advance(&mut self, length: usize, interpolate: Option<fn(&T, &T, f32) -> T>) -> T {
+        if let Some(interpolate) = interpolate {
+            if self.position < length {
+                self.position += 1;
+                self.value = if self.position == length {
+                    self.target
+                } else {
+                    interpolate(
+                        &self.start,
+                        &self.target,
+                        self.position as f32 / length as f32,
+                    )
+                };
+            }
+        }
+        self.value
+    }
+}
+
+/// A pipe that multiplies its input items with a scale and adds an offset.
+///
+/// Every call returns `input * scale + offset`. In contrast to a [`Lazy`](struct.Lazy.html) pipe that does the same, the scale and offset can be changed afterwards. By default, changes take effect immediately, but if the pipe was created with [`with_ramp`](#method.with_ramp), the parameters glide linearly to their new values within the given number of calls, which avoids clicks in audio signals.
+///
+/// Resetting this pipe restores the scale and offset it was created with.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipe = ScaleOffset::new(2, 1);
+/// assert_eq!(7, pipe.next(3));
+///
+/// pipe.set_scale(3);
+/// assert_eq!(10, pipe.next(3));
+///
+/// let mut pipe = ScaleOffset::new(1.0, 0.0).with_ramp(2);
+/// pipe.set_scale(2.0);
+/// assert_eq!(1.5, pipe.next(1.0));
+/// assert_eq!(2.0, pipe.next(1.0));
+/// ```
+pub struct ScaleOffset<T>
+where
+    T: Mul<Output = T> + Add<Output = T> + Copy,
+{
+    initial_scale: T,
+    initial_offset: T,
+    scale: Ramp<T>,
+    offset: Ramp<T>,
+    ramp_length: usize,
+    interpolate: Option<fn(&T, &T, f32) -> T>,
+}
+
+impl<T> ScaleOffset<T>
+where
+    T: Mul<Output = T> + Add<Output = T> + Copy,
+{
+    /// Create a new pipe with the given scale and offset.
+    pub fn new(scale: T, offset: T) -> Self {
+        Self {
+            initial_scale: scale,
+            initial_offset: offset,
+            scale: Ramp::new(scale),
+            offset: Ramp::new(offset),
+            ramp_length: 0,
+            interpolate: None,
+        }
+    }
+
+    /// Return the scale.
+    ///
+    /// If the scale is currently gliding, this is the target of the glide.
+    pub fn scale(&self) -> T {
+        self.scale.target
+    }
+
+    /// Set the scale.
+    pub fn set_scale(&mut self, scale: T) {
+        self.scale.set(scale, self.ramp_length, self.interpolate);
+    }
+
+    /// Return the offset.
+    ///
+    /// If the offset is currently gliding, this is the target of the glide.
+    pub fn offset(&self) -> T {
+        self.offset.target
+    }
+
+    /// Set the offset.
+    pub fn set_offset(&mut self, offset: T) {
+        self.offset.set(offset, self.ramp_length, self.interpolate);
+    }
+}
+
+impl<T> ScaleOffset<T>
+where
+    T: Mul<Output = T> + Add<Output = T> + Copy + Interpolate,
+{
+    /// Let parameter changes glide linearly to their new values within `ramp_length` calls.
+    ///
+    /// The new value is reached exactly on the `ramp_length`-th call after the change. A ramp length of zero disables the gliding.
+    pub fn with_ramp(mut self, ramp_length: usize) -> Self {
+        self.ramp_length = ramp_length;
+        self.interpolate = Some(T::interpolate);
+        self
+    }
+}
+
+impl<T> Pipe for ScaleOffset<T>
+where
+    T: Mul<Output = T> + Add<Output = T> + Copy,
+{
+    type InputItem = T;
+    type OutputItem = T;
+
+    fn next(&mut self, item: T) -> T {
+        let scale = self.scale.advance(self.ramp_length, self.interpolate);
+        let offset = self.offset.advance(self.ramp_length, self.interpolate);
+        item * scale + offset
+    }
+}
+
+impl<T> ResetablePipe for ScaleOffset<T>
+where
+    T: Mul<Output = T> + Add<Output = T> + Copy,
+{
+    fn reset(&mut self) {
+        self.scale = Ramp::new(self.initial_scale);
+        self.offset = Ramp::new(self.initial_offset);
+    }
+}
+
+/// A pipe that maps indices to the entries of a lookup table.
+///
+/// Indices beyond the end of the table are clamped to the last entry. If fractional positions should be looked up, use the [`InterpolatedLut`](struct.InterpolatedLut.html).
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut lut = Lut::new(vec!['a', 'b', 'c']);
+/// assert_eq!('a', lut.next(0));
+/// assert_eq!('c', lut.next(2));
+/// assert_eq!('c', lut.next(3));
+/// ```
+pub struct Lut<T: Clone> {
+    table: Vec<T>,
+}
+
+impl<T: Clone> Lut<T> {
+    /// Create a new lookup table pipe.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table is empty.
+    pub fn new(table: Vec<T>) -> Self {
+        assert!(!table.is_empty(), "A lookup table must not be empty");
+        Self { table }
+    }
+
+    /// Return the table.
+    pub fn table(&self) -> &[T] {
+        &self.table
+    }
+}
+
+impl<T: Clone> Pipe for Lut<T> {
+    type InputItem = usize;
+    type OutputItem = T;
+
+    fn next(&mut self, index: usize) -> T {
+        self.table[index.min(self.table.len() - 1)].clone()
+    }
+}
+
+impl<T: Clone> ResetablePipe for Lut<T> {
+    fn reset(&mut self) {}
+}
+
+/// The way an [`InterpolatedLut`](struct.InterpolatedLut.html) handles the ends of its table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LutEdge {
+    /// The table is periodic: The last entry is followed by the first one and positions outside of `[0.0, 1.0)` wrap around.
+    Wrap,
+    /// Positions after the last entry return the last entry and positions before the first entry return the first entry.
+    Clamp,
+}
+
+/// A pipe that reads a lookup table at fractional positions.
+///
+/// The input range `[0.0, 1.0)` is mapped onto the whole table: The position `x` lies between the entries `floor(x * len)` and the one after it, and the output is interpolated linearly between them. With [`LutEdge::Wrap`](enum.LutEdge.html#variant.Wrap), the segment after the last entry leads back to the first entry, which turns this pipe into a wavetable oscillator when it's fed by a [`Phasor`](struct.Phasor.html). With [`LutEdge::Clamp`](enum.LutEdge.html#variant.Clamp), the last segment holds the last entry.
+///
+/// This pipe doesn't allocate when it's called. `NaN` positions are looked up at `0.0`.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut wavetable = Phasor::new(0.0, 0.125).compose()
+///     >> InterpolatedLut::new(vec![0.0, 1.0, 0.0, -1.0], LutEdge::Wrap);
+/// let signal: Vec<f32> = (0..8).map(|_| wavetable.next(())).collect();
+/// assert_eq!(vec![0.0, 0.5, 1.0, 0.5, 0.0, -0.5, -1.0, -0.5], signal);
+/// ```
+pub struct InterpolatedLut<T: Interpolate> {
+    table: Vec<T>,
+    edge: LutEdge,
+}
+
+impl<T: Interpolate> InterpolatedLut<T> {
+    /// Create a new interpolating lookup table pipe.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table is empty.
+    pub fn new(table: Vec<T>, edge: LutEdge) -> Self {
+        assert!(!table.is_empty(), "A lookup table must not be empty");
+        Self { table, edge }
+    }
+
+    /// Return the table.
+    pub fn table(&self) -> &[T] {
+        &self.table
+    }
+}
+
+impl<T: Interpolate> Pipe for InterpolatedLut<T> {
+    type InputItem = f32;
+    type OutputItem = T;
+
+    fn next(&mut self, position: f32) -> T {
+        let len = self.table.len();
+        let position = if position.is_nan() { 0.0 } else { position };
+        let position = match self.edge {
+            LutEdge::Wrap => position.rem_euclid(1.0) * len as f32,
+            LutEdge::Clamp => position.clamp(0.0, 1.0) * len as f32,
+        };
+
+        // `rem_euclid` and the multiplication may round up to exactly `len`.
+        let index = (position.floor() as usize).min(len - 1);
+        let fraction = (position - index as f32).min(1.0);
+        let next_index = match self.edge {
+            LutEdge::Wrap => (index + 1) % len,
+            LutEdge::Clamp => (index + 1).min(len - 1),
+        };
+        self.table[index].interpolate(&self.table[next_index], fraction)
+    }
+}
+
+impl<T: Interpolate> ResetablePipe for InterpolatedLut<T> {
+    fn reset(&mut self) {}
+}
+
+/// The bin of a [`Histogram`](struct.Histogram.html) that a value was counted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistogramBin {
+    /// The value is less than the lowest boundary.
+    Underflow,
+    /// The value is in the bucket with the given index.
+    Bucket(usize),
+    /// The value is greater than or equal to the highest boundary.
+    Overflow,
+    /// The value is not a number.
+    NaN,
+}
+
+/// A pipe that counts its input items in buckets.
+///
+/// The buckets are defined by increasing boundaries, and `n + 1` boundaries define `n` buckets. The buckets are half-open: The bucket with the index `i` contains the values `v` with `boundaries[i] <= v < boundaries[i + 1]`. Values below the lowest boundary are counted as underflow, values greater than or equal to the highest boundary are counted as overflow and `NaN`s are counted separately.
+///
+/// The output item is the bin the input item was counted in. In order to observe a chain of pipes, the histogram can be borrowed mutably and attached with [`bypass`](trait.Pipe.html#method.bypass), which keeps the counts available after processing. Resetting this pipe zeroes all counts.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+/// use iterpipes::dsp::Sine;
+///
+/// let mut histogram: Histogram<f32> = Histogram::uniform(-1.0, 1.0, 4);
+/// {
+///     let mut chain = Sine::new(375.0, 48000.0).compose()
+///         >> (&mut histogram).bypass()
+///         >> Lazy::new(|(sample, _): (f32, HistogramBin)| sample * 0.5);
+///     for _ in 0..128 {
+///         chain.next(());
+///     }
+/// }
+///
+/// assert_eq!(128, histogram.total());
+/// // A sine wave spends more time near its peaks than near zero.
+/// assert!(histogram.count(0) > histogram.count(1));
+/// assert!(histogram.count(3) > histogram.count(2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Histogram<T>
+where
+    T: Into<f64> + Copy,
+{
+    boundaries: Vec<f64>,
+    counts: Vec<u64>,
+    underflow: u64,
+    overflow: u64,
+    nan: u64,
+    item: PhantomData<T>,
+}
+
+impl<T> Histogram<T>
+where
+    T: Into<f64> + Copy,
+{
+    /// Create a new histogram with the given bucket boundaries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are less than two boundaries or if they aren't strictly increasing.
+    pub fn new(boundaries: Vec<f64>) -> Self {
+        assert!(
+            boundaries.len() >= 2,
+            "A histogram needs at least two boundaries"
+        );
+        assert!(
+            boundaries.windows(2).all(|pair| pair[0] < pair[1]),
+            "The boundaries of a histogram must be strictly increasing"
+        );
+        Self {
+            counts: vec![0; boundaries.len() - 1],
+            boundaries,
+            underflow: 0,
+            overflow: 0,
+            nan: 0,
+            item: PhantomData,
+        }
+    }
+
+    /// Create a new histogram with `count` buckets of equal width between `min` and `max`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is zero or if `min` isn't less than `max`.
+    pub fn uniform(min: f64, max: f64, count: usize) -> Self {
+        assert!(count != 0, "A histogram needs at least one bucket");
+        let width = (max - min) / count as f64;
+        let mut boundaries: Vec<f64> = (0..count).map(|i| min + width * i as f64).collect();
+        boundaries.push(max);
+        Self::new(boundaries)
+    }
+
+    /// Return the bin of a value without counting it.
+    pub fn bin(&self, value: T) -> HistogramBin {
+        let value: f64 = value.into();
+        if value.is_nan() {
+            return HistogramBin::NaN;
+        }
+        match self
+            .boundaries
+            .partition_point(|boundary| *boundary <= value)
+        {
+            0 => HistogramBin::Underflow,
+            index if index == self.boundaries.len() => HistogramBin::Overflow,
+            index => HistogramBin::Bucket(index - 1),
+        }
+    }
+
+    /// Return the bucket boundaries.
+    pub fn boundaries(&self) -> &[f64] {
+        &self.boundaries
+    }
+
+    /// Return the counts of all buckets.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Return the count of the bucket with the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn count(&self, index: usize) -> u64 {
+        self.counts[index]
+    }
+
+    /// Return the number of values below the lowest boundary.
+    pub fn underflow(&self) -> u64 {
+        self.underflow
+    }
+
+    /// Return the number of values greater than or equal to the highest boundary.
+    pub fn overflow(&self) -> u64 {
+        self.overflow
+    }
+
+    /// Return the number of `NaN` values.
+    pub fn nan(&self) -> u64 {
+        self.nan
+    }
+
+    /// Return the number of all counted values, including underflow, overflow and `NaN`s.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum::<u64>() + self.underflow + self.overflow + self.nan
+    }
+}
+
+impl<T> Pipe for Histogram<T>
+where
+    T: Into<f64> + Copy,
+{
+    type InputItem = T;
+    type OutputItem = HistogramBin;
+
+    fn next(&mut self, item: T) -> HistogramBin {
+        let bin = self.bin(item);
+        match bin {
+            HistogramBin::Underflow => self.underflow += 1,
+            HistogramBin::Bucket(index) => self.counts[index] += 1,
+            HistogramBin::Overflow => self.overflow += 1,
+            HistogramBin::NaN => self.nan += 1,
+        }
+        bin
+    }
+}
+
+impl<T> ResetablePipe for Histogram<T>
+where
+    T: Into<f64> + Copy,
+{
+    fn reset(&mut self) {
+        for count in self.counts.iter_mut() {
+            *count = 0;
+        }
+        self.underflow = 0;
+        self.overflow = 0;
+        self.nan = 0;
+    }
+}
+
+/// An integer sample format that a [`Quantize`](struct.Quantize.html) pipe can produce.
+pub trait QuantizedSample: Copy {
+    /// The number of bits of the format.
+    const BITS: u32;
+
+    /// Convert a value that is already in the range of the format.
+    fn from_i32(value: i32) -> Self;
+}
+
+impl QuantizedSample for i8 {
+    const BITS: u32 = 8;
+
+    fn from_i32(value: i32) -> Self {
+        value as i8
+    }
+}
+
+impl QuantizedSample for i16 {
+    const BITS: u32 = 16;
+
+    fn from_i32(value: i32) -> Self {
+        value as i16
+    }
+}
+
+/// A 24-bit sample, stored in the lower bits of an `i32`.
+///
+/// The wrapped value is always in the range `[-8_388_608, 8_388_607]` when it is produced by a [`Quantize`](struct.Quantize.html) pipe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct I24(pub i32);
+
+impl QuantizedSample for I24 {
+    const BITS: u32 = 24;
+
+    fn from_i32(value: i32) -> Self {
+        I24(value)
+    }
+}
+
+/// A pipe that quantizes floating point samples to integer samples.
+///
+/// The input samples are clamped to `[-1.0, 1.0]` and scaled symmetrically, so `1.0` is mapped to the largest value of the format and `-1.0` to its negation. For `i16`, this is `32767` and `-32767`. The scaled value is rounded to the nearest integer, with halfway cases rounded away from zero. `NaN`s are quantized to zero. Besides `i16`, the formats `i8` and 24-bit samples wrapped in an [`I24`](struct.I24.html) are supported.
+///
+/// Optionally, triangular (TPDF) dither with an amplitude of one step is added before rounding, which decorrelates the quantization error from the signal. The dither is generated by a [`XorShift`](dsp/struct.XorShift.html) generator, and resetting this pipe restores its seed, so the same dither sequence is generated again.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut quantize: Quantize<i16> = Quantize::new();
+/// assert_eq!(32767, quantize.next(1.0));
+/// assert_eq!(0, quantize.next(0.0));
+/// assert_eq!(-16384, quantize.next(-0.5));
+/// assert_eq!(-32767, quantize.next(-1.5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Quantize<T: QuantizedSample = i16> {
+    dither: Option<(u64, XorShift)>,
+    sample: PhantomData<T>,
+}
+
+impl<T: QuantizedSample> Quantize<T> {
+    /// Create a new quantizer without dither.
+    pub fn new() -> Self {
+        Self {
+            dither: None,
+            sample: PhantomData,
+        }
+    }
+
+    /// Create a new quantizer that adds TPDF dither generated from the given seed.
+    pub fn dithered(seed: u64) -> Self {
+        Self {
+            dither: Some((seed, XorShift::new(seed))),
+            sample: PhantomData,
+        }
+    }
+
+    /// Return the largest value of the format.
+    fn max() -> f32 {
+        ((1i32 << (T::BITS - 1)) - 1) as f32
+    }
+}
+
+impl<T: QuantizedSample> Default for Quantize<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: QuantizedSample> Pipe for Quantize<T> {
+    type InputItem = f32;
+    type OutputItem = T;
+
+    fn next(&mut self, sample: f32) -> T {
+        let max = Self::max();
+        let mut value = sample.clamp(-1.0, 1.0) * max;
+        if let Some((_, rng)) = self.dither.as_mut() {
+            value += rng.next_f32() - rng.next_f32();
+        }
+        // The cast maps `NaN` to zero and the clamp keeps the dither in range.
+        let value = value.round().clamp(-max - 1.0, max) as i32;
+        T::from_i32(value)
+    }
+}
+
+impl<T: QuantizedSample> ResetablePipe for Quantize<T> {
+    fn reset(&mut self) {
+        if let Some((seed, rng)) = self.dither.as_mut() {
+            *rng = XorShift::new(*seed);
+        }
+    }
+}
+
+#[test]
+fn smoother_linear() {
+    let mut smoother = Smoother::linear(0.0, 0.1);
+
+    // A step from 0.0 to 1.0 takes 10 calls and ends exactly on the target.
+    let mut calls = 0;
+    while smoother.next(1.0) != 1.0 {
+        calls += 1;
+        assert!(calls < 10);
+    }
+    assert_eq!(9, calls);
+    assert_eq!(1.0, smoother.value());
+
+    // Changing the target mid-ramp turns the ramp around.
+    smoother.reset();
+    assert_eq!(0.0, smoother.value());
+    smoother.next(1.0);
+    smoother.next(1.0);
+    let value = smoother.next(-1.0);
+    assert!((value - 0.1).abs() < 1e-6);
+    for _ in 0..10 {
+        smoother.next(-1.0);
+    }
+    assert_eq!(-1.0, smoother.next(-1.0));
+}
+
+#[test]
+#[should_panic(expected = "The maximal delta of a smoother must be positive and finite")]
+fn smoother_negative_delta() {
+    Smoother::linear(0.0, -0.1);
+}
+
+#[test]
+#[should_panic(expected = "The maximal delta of a smoother must be positive and finite")]
+fn smoother_nan_delta() {
+    Smoother::new(
+        0.0,
+        SmoothingMode::Linear {
+            max_delta: f32::NAN,
+        },
+    );
+}
+
+#[test]
+fn smoother_exponential() {
+    let mut smoother = Smoother::exponential(0.0, 100.0);
+
+    let mut value = 0.0;
+    for _ in 0..100 {
+        value = smoother.next(1.0);
+    }
+    // After one time constant, about 63% of the distance is covered.
+    assert!((value - (1.0 - (-1.0f32).exp())).abs() < 1e-3);
+
+    for _ in 0..10_000 {
+        value = smoother.next(1.0);
+    }
+    assert_eq!(1.0, value);
+
+    smoother.reset();
+    assert_eq!(0.0, smoother.value());
+}
+
+#[test]
+fn difference_and_running_sum() {
+    use crate::dsp::XorShift;
+    use crate::testing::assert_pipe_matches;
+    use std::num::Wrapping;
+
+    // The running sum of the differences reconstructs the input.
+    let mut rng = XorShift::new(5);
+    let inputs: Vec<i64> = (0..1024).map(|_| (rng.next_u64() >> 40) as i64).collect();
+    assert_pipe_matches(
+        Difference::new(0).compose() >> RunningSum::new(0),
+        |i: i64| i,
+        inputs.clone(),
+    );
+
+    // With zero as the first difference, the first item must be the initial value of the sum.
+    let mut pipe = Difference::zero_first().compose() >> RunningSum::new(inputs[0]);
+    for input in inputs.iter() {
+        assert_eq!(*input, pipe.next(*input));
+    }
+
+    // Wrapping integers wrap around and still reconstruct the input.
+    let inputs: Vec<Wrapping<u8>> = (0..1024).map(|_| Wrapping(rng.next_u64() as u8)).collect();
+    assert_pipe_matches(
+        Difference::new(Wrapping(0)).compose() >> RunningSum::new(Wrapping(0)),
+        |i: Wrapping<u8>| i,
+        inputs,
+    );
+
+    let mut sum = RunningSum::new(Wrapping(250u8));
+    assert_eq!(Wrapping(4), sum.next(Wrapping(10)));
+    let mut difference = Difference::new(Wrapping(10u8));
+    assert_eq!(Wrapping(251), difference.next(Wrapping(5)));
+
+    sum.reset();
+    assert_eq!(Wrapping(251), sum.next(Wrapping(1)));
+    difference.reset();
+    assert_eq!(Wrapping(0), difference.next(Wrapping(10)));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic]
+fn running_sum_overflow() {
+    let mut sum = RunningSum::new(250u8);
+    sum.next(10);
+}
+
+#[test]
+fn clamp() {
+    let mut clamp = Clamp::new(2u8, 5);
+    let outputs: Vec<u8> = (0..8).map(|i| clamp.next(i)).collect();
+    assert_eq!(vec![2, 2, 2, 3, 4, 5, 5, 5], outputs);
+
+    let mut clamp = Clamp::new(-1.0f64, 1.0);
+    assert_eq!(1.0, clamp.next(f64::INFINITY));
+    assert_eq!(-1.0, clamp.next(f64::NEG_INFINITY));
+    assert!(clamp.next(f64::NAN).is_nan());
+
+    let mut clamp = clamp.with_nan_policy(NanPolicy::ClampToMin);
+    assert_eq!(-1.0, clamp.next(f64::NAN));
+    assert_eq!(0.5, clamp.next(0.5));
+}
+
+#[test]
+#[should_panic(expected = "The minimum of a clamp must be less than or equal to its maximum")]
+fn clamp_invalid_range() {
+    Clamp::new(1.0, -1.0);
+}
+
+#[test]
+fn saturate() {
+    for curve in [SaturationCurve::Tanh, SaturationCurve::Cubic].iter() {
+        let mut saturate = Saturate::new(*curve);
+        let mut previous = -1.0;
+        for i in -1000..=1000 {
+            let input = i as f32 / 100.0;
+            let output = saturate.next(input);
+            assert!((-1.0..=1.0).contains(&output));
+            assert!(output >= previous);
+            assert_eq!(-output, saturate.next(-input));
+            previous = output;
+        }
+        assert!(saturate.next(f32::NAN).is_nan());
+    }
+
+    let mut saturate = Saturate::tanh();
+    assert!((saturate.next(0.01) - 0.01).abs() < 1e-6);
+
+    let mut saturate = Saturate::cubic();
+    assert_eq!(0.6875, saturate.next(0.5));
+    assert_eq!(1.0, saturate.next(100.0));
+}
+
+#[test]
+fn scale_offset() {
+    // Fixed-point values with eight fractional bits.
+    let mut pipe = ScaleOffset::new(3i32, 256);
+    assert_eq!(256 + 3 * 128, pipe.next(128));
+    pipe.set_offset(-256);
+    assert_eq!(-256 + 3 * 128, pipe.next(128));
+    pipe.set_scale(-1);
+    assert_eq!((-1, -256), (pipe.scale(), pipe.offset()));
+    assert_eq!(-384, pipe.next(128));
+    pipe.reset();
+    assert_eq!((3, 256), (pipe.scale(), pipe.offset()));
+    assert_eq!(640, pipe.next(128));
+
+    let mut pipe = ScaleOffset::new(0.5f32, 0.0);
+    assert_eq!(1.0, pipe.next(2.0));
+    pipe.set_scale(2.0);
+    assert_eq!(4.0, pipe.next(2.0));
+}
+
+#[test]
+fn scale_offset_ramp() {
+    let mut pipe = ScaleOffset::new(1.0f32, 0.0).with_ramp(4);
+    assert_eq!(1.0, pipe.next(1.0));
+
+    pipe.set_scale(3.0);
+    pipe.set_offset(-4.0);
+    assert_eq!(3.0, pipe.scale());
+    let outputs: Vec<f32> = (0..5).map(|_| pipe.next(1.0)).collect();
+    assert_eq!(vec![0.5, 0.0, -0.5, -1.0, -1.0], outputs);
+
+    // Changing a parameter mid-ramp glides from the current value.
+    pipe.set_scale(1.0);
+    pipe.next(0.0);
+    pipe.next(0.0);
+    pipe.set_scale(3.0);
+    let outputs: Vec<f32> = (0..4).map(|_| pipe.next(1.0) + 4.0).collect();
+    assert_eq!(vec![2.25, 2.5, 2.75, 3.0], outputs);
+
+    pipe.set_scale(5.0);
+    pipe.next(1.0);
+    pipe.reset();
+    assert_eq!(1.0, pipe.next(1.0));
+
+    let mut pipe = ScaleOffset::new(1.0f64, 0.0).with_ramp(0);
+    pipe.set_scale(2.0);
+    assert_eq!(2.0, pipe.next(1.0));
+}
+
+#[test]
+fn lut() {
+    let mut lut = Lut::new(vec![1u8, 2, 4, 8]);
+    let outputs: Vec<u8> = (0..6).map(|i| lut.next(i)).collect();
+    assert_eq!(vec![1, 2, 4, 8, 8, 8], outputs);
+    assert_eq!(&[1, 2, 4, 8], lut.table());
+}
+
+#[test]
+fn interpolated_lut() {
+    let table = vec![0.0f32, 4.0, 8.0, 16.0];
+
+    let mut lut = InterpolatedLut::new(table.clone(), LutEdge::Wrap);
+    assert_eq!(0.0, lut.next(0.0));
+    assert_eq!(2.0, lut.next(0.125));
+    assert_eq!(4.0, lut.next(0.25));
+    assert_eq!(12.0, lut.next(0.625));
+    // The segment between the last and the first entry.
+    assert_eq!(16.0, lut.next(0.75));
+    assert_eq!(12.0, lut.next(0.8125));
+    assert_eq!(4.0, lut.next(0.9375));
+    // Positions outside of the range wrap around.
+    assert_eq!(2.0, lut.next(1.125));
+    assert_eq!(12.0, lut.next(-0.1875));
+
+    let mut lut = InterpolatedLut::new(table, LutEdge::Clamp);
+    assert_eq!(12.0, lut.next(0.625));
+    assert_eq!(16.0, lut.next(0.75));
+    assert_eq!(16.0, lut.next(0.9375));
+    assert_eq!(16.0, lut.next(1.0));
+    assert_eq!(16.0, lut.next(7.0));
+    assert_eq!(0.0, lut.next(-1.0));
+    assert_eq!(0.0, lut.next(f32::NAN));
+
+    let mut lut = InterpolatedLut::new(vec![3.0f64], LutEdge::Wrap);
+    assert_eq!(3.0, lut.next(0.5));
+    assert_eq!(3.0, lut.next(-1e-9));
+}
+
+#[test]
+#[should_panic(expected = "A lookup table must not be empty")]
+fn lut_empty() {
+    Lut::<u8>::new(Vec::new());
+}
+
+#[test]
+fn histogram() {
+    let mut histogram: Histogram<f64> = Histogram::new(vec![0.0, 1.0, 2.0, 4.0]);
+    let values = [
+        -0.5,
+        0.0,
+        0.5,
+        0.999,
+        1.0,
+        1.5,
+        2.0,
+        3.0,
+        3.5,
+        3.999,
+        4.0,
+        10.0,
+        f64::NAN,
+    ];
+    let bins: Vec<HistogramBin> = values.iter().map(|v| histogram.next(*v)).collect();
+
+    use HistogramBin::*;
+    assert_eq!(
+        vec![
+            Underflow,
+            Bucket(0),
+            Bucket(0),
+            Bucket(0),
+            Bucket(1),
+            Bucket(1),
+            Bucket(2),
+            Bucket(2),
+            Bucket(2),
+            Bucket(2),
+            Overflow,
+            Overflow,
+            NaN
+        ],
+        bins
+    );
+    assert_eq!(&[3, 2, 4], histogram.counts());
+    assert_eq!(1, histogram.underflow());
+    assert_eq!(2, histogram.overflow());
+    assert_eq!(1, histogram.nan());
+    assert_eq!(13, histogram.total());
+
+    histogram.reset();
+    assert_eq!(&[0, 0, 0], histogram.counts());
+    assert_eq!(0, histogram.total());
+}
+
+#[test]
+fn histogram_uniform() {
+    let mut histogram: Histogram<u8> = Histogram::uniform(0.0, 200.0, 10);
+    assert_eq!(11, histogram.boundaries().len());
+    for i in 0..=255u8 {
+        histogram.next(i);
+    }
+    assert!(histogram.counts().iter().all(|count| *count == 20));
+    assert_eq!(0, histogram.underflow());
+    assert_eq!(56, histogram.overflow());
+}
+
+#[test]
+#[should_panic(expected = "The boundaries of a histogram must be strictly increasing")]
+fn histogram_unordered() {
+    Histogram::<f32>::new(vec![0.0, 2.0, 1.0]);
+}
+
+#[test]
+fn quantize() {
+    let mut quantize: Quantize<i16> = Quantize::new();
+    assert_eq!(-32767, quantize.next(-1.0));
+    assert_eq!(0, quantize.next(0.0));
+    assert_eq!(32767, quantize.next(1.0));
+    assert_eq!(32767, quantize.next(1.5));
+    assert_eq!(-32767, quantize.next(-1.5));
+    assert_eq!(0, quantize.next(f32::NAN));
+    // Rounding to the nearest step.
+    assert_eq!(1, quantize.next(0.6 / 32767.0));
+    assert_eq!(0, quantize.next(0.4 / 32767.0));
+    assert_eq!(-1, quantize.next(-0.6 / 32767.0));
+
+    let mut quantize: Quantize<i8> = Quantize::new();
+    assert_eq!(127, quantize.next(1.0));
+    assert_eq!(-127, quantize.next(-1.0));
+
+    let mut quantize: Quantize<I24> = Quantize::new();
+    assert_eq!(I24(8_388_607), quantize.next(1.0));
+    assert_eq!(I24(-8_388_607), quantize.next(-1.0));
+    assert_eq!(I24(1), quantize.next(0.6 / 8_388_607.0));
+}
+
+#[test]
+fn quantize_dither() {
+    let mut quantize: Quantize<i16> = Quantize::dithered(5);
+    let input = 0.25;
+    let expected = input * 32767.0;
+
+    let outputs: Vec<i16> = (0..100_000).map(|_| quantize.next(input)).collect();
+    let mean = outputs.iter().map(|o| *o as f64).sum::<f64>() / outputs.len() as f64;
+    assert!((mean - expected as f64).abs() < 0.02);
+    // The dither is at most one step and rounding adds at most half a step.
+    assert!(outputs.iter().all(|o| (*o as f32 - expected).abs() <= 1.5));
+    assert!(outputs.iter().any(|o| *o != outputs[0]));
+
+    // Resetting reproduces the same dither sequence.
+    quantize.reset();
+    let repeated: Vec<i16> = (0..1000).map(|_| quantize.next(input)).collect();
+    assert_eq!(&outputs[..1000], &repeated[..]);
+
+    // The dither never leaves the range of the format.
+    assert!((0..1000).all(|_| quantize.next(1.0) >= 32766));
+}
@@ -0,0 +1,682 @@
+use crate::{Pipe, ResetablePipe};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// An online accumulator for the count, minimum, maximum, mean and variance of a series of values.
+///
+/// This uses [Welford's algorithm](https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance#Welford's_online_algorithm), which is numerically stable.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Welford {
+    count: usize,
+    min: f64,
+    max: f64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    pub(crate) fn new() -> Self {
+        Self {
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, value: f64) {
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    pub(crate) fn snapshot(&self) -> MetricsSnapshot {
+        if self.count == 0 {
+            MetricsSnapshot {
+                count: 0,
+                min: f64::NAN,
+                max: f64::NAN,
+                mean: f64::NAN,
+                variance: f64::NAN,
+            }
+        } else {
+            MetricsSnapshot {
+                count: self.count,
+                min: self.min,
+                max: self.max,
+                mean: self.mean,
+                variance: self.m2 / self.count as f64,
+            }
+        }
+    }
+}
+
+/// A snapshot of the statistics collected by a [`Metrics`](struct.Metrics.html) pipe.
+///
+/// If no items were observed yet, `count` is zero and all other values are `NaN`. The variance is the population variance of the observed values, so it's zero if only one value was observed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricsSnapshot {
+    /// The number of observed items.
+    pub count: usize,
+    /// The smallest observed value.
+    pub min: f64,
+    /// The largest observed value.
+    pub max: f64,
+    /// The mean of the observed values.
+    pub mean: f64,
+    /// The population variance of the observed values.
+    pub variance: f64,
+}
+
+/// A pipe that collects statistics about the output items of another pipe.
+///
+/// Every output item of the wrapped pipe is projected to an `f64` value and passed through unchanged. The count, minimum, maximum, mean and variance of these values are updated online and can be retrieved with [`snapshot`](#method.snapshot). Resetting this pipe clears the statistics along with the wrapped pipe.
+///
+/// For more information, please see [the documentation of the `metrics` method](trait.Pipe.html#method.metrics).
+pub struct Metrics<P, F>
+where
+    P: Pipe,
+    F: FnMut(&P::OutputItem) -> f64,
+{
+    pipe: P,
+    projection: F,
+    accumulator: Welford,
+}
+
+impl<P, F> Metrics<P, F>
+where
+    P: Pipe,
+    F: FnMut(&P::OutputItem) -> f64,
+{
+    /// Create a new metrics pipe with a projection function.
+    pub fn new(pipe: P, projection: F) -> Self {
+        Self {
+            pipe,
+            projection,
+            accumulator: Welford::new(),
+        }
+    }
+
+    /// Return the current statistics.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.accumulator.snapshot()
+    }
+
+    /// Return a reference to the wrapped pipe.
+    pub fn inner(&self) -> &P {
+        &self.pipe
+    }
+
+    /// Return a mutable reference to the wrapped pipe.
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.pipe
+    }
+}
+
+impl<P, F> Pipe for Metrics<P, F>
+where
+    P: Pipe,
+    F: FnMut(&P::OutputItem) -> f64,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = P::OutputItem;
+
+    fn next(&mut self, item: P::InputItem) -> P::OutputItem {
+        let item = self.pipe.next(item);
+        self.accumulator.push((self.projection)(&item));
+        item
+    }
+
+    fn finish(&mut self) -> Vec<P::OutputItem> {
+        let items = self.pipe.finish();
+        for item in &items {
+            self.accumulator.push((self.projection)(item));
+        }
+        items
+    }
+}
+
+impl<P, F> ResetablePipe for Metrics<P, F>
+where
+    P: ResetablePipe,
+    F: FnMut(&P::OutputItem) -> f64,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+        self.accumulator = Welford::new();
+    }
+}
+
+/// Convert a numeric item into an `f64`. This is the default projection of [`Metrics`](struct.Metrics.html).
+pub(crate) fn into_f64<T: Copy + Into<f64>>(item: &T) -> f64 {
+    (*item).into()
+}
+
+/// A snapshot of the statistics returned by a [`Stats`](struct.Stats.html) pipe.
+///
+/// This is the same type as the snapshot of a [`Metrics`](struct.Metrics.html) pipe.
+pub type StatsSnapshot = MetricsSnapshot;
+
+/// A pipe that returns the running statistics of its input items.
+///
+/// Every input item is converted into an `f64` and incorporated into the statistics, and the output item is a snapshot of the statistics after the item was incorporated. In contrast to [`Metrics`](struct.Metrics.html), which observes the output items of another pipe, the statistics themselves are the output stream of this pipe, so they can be processed further, for example decimated for a display.
+///
+/// The statistics are calculated with [Welford's algorithm](https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance#Welford's_online_algorithm), which is numerically stable. The variance is the population variance, so it's zero after the first sample. Resetting this pipe clears the statistics.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut stats: Stats<f32> = Stats::new();
+/// stats.next(1.0);
+/// let snapshot = stats.next(3.0);
+///
+/// assert_eq!(2, snapshot.count);
+/// assert_eq!(2.0, snapshot.mean);
+/// assert_eq!(1.0, snapshot.variance);
+/// assert_eq!((1.0, 3.0), (snapshot.min, snapshot.max));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Stats<T>
+where
+    T: Into<f64>,
+{
+    accumulator: Welford,
+    item: PhantomData<T>,
+}
+
+impl<T: Into<f64>> Stats<T> {
+    /// Create a new statistics pipe.
+    pub fn new() -> Self {
+        Self {
+            accumulator: Welford::new(),
+            item: PhantomData,
+        }
+    }
+
+    /// Return the current statistics.
+    ///
+    /// If no items were processed yet, `count` is zero and all other values are `NaN`.
+    pub fn snapshot(&self) -> StatsSnapshot {
+        self.accumulator.snapshot()
+    }
+}
+
+impl<T: Into<f64>> Default for Stats<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Into<f64>> Pipe for Stats<T> {
+    type InputItem = T;
+    type OutputItem = StatsSnapshot;
+
+    fn next(&mut self, item: T) -> StatsSnapshot {
+        self.accumulator.push(item.into());
+        self.accumulator.snapshot()
+    }
+}
+
+impl<T: Into<f64>> ResetablePipe for Stats<T> {
+    fn reset(&mut self) {
+        self.accumulator = Welford::new();
+    }
+}
+
+/// The timing statistics collected by a [`Profile`](struct.Profile.html) pipe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProfileStats {
+    /// The number of calls to `next`.
+    pub calls: usize,
+    /// The number of calls whose duration was measured.
+    pub measured_calls: usize,
+    /// The accumulated duration of all measured calls.
+    pub total: Duration,
+    /// The longest duration of a single measured call.
+    pub max: Duration,
+}
+
+impl ProfileStats {
+    /// Return the mean duration of a measured call, or `None` if no call was measured yet.
+    pub fn mean(&self) -> Option<Duration> {
+        if self.measured_calls == 0 {
+            None
+        } else {
+            // Dividing in nanoseconds avoids truncating the number of calls to `u32`.
+            let nanos = self.total.as_nanos() / self.measured_calls as u128;
+            Some(Duration::new(
+                (nanos / 1_000_000_000) as u64,
+                (nanos % 1_000_000_000) as u32,
+            ))
+        }
+    }
+}
+
+/// A pipe that measures the processing time of another pipe.
+///
+/// Every `sample_interval`-th call to `next` is timed with [`Instant`](https://doc.rust-lang.org/std/time/struct.Instant.html), starting with the first one. The statistics can be retrieved with [`stats`](#method.stats). Since `Instant::now()` has an overhead on its own, a larger sample interval may be used to reduce the impact of the measurement.
+///
+/// The statistics are independent of the state of the wrapped pipe: Resetting this pipe only resets the wrapped pipe and the statistics are only cleared by [`reset_stats`](#method.reset_stats). Finishing this pipe isn't measured.
+///
+/// For more information, please see [the documentation of the `profile` method](trait.Pipe.html#method.profile).
+pub struct Profile<P>
+where
+    P: Pipe,
+{
+    pipe: P,
+    sample_interval: usize,
+    stats: ProfileStats,
+}
+
+impl<P: Pipe> Profile<P> {
+    /// Create a new profiling pipe that measures every `sample_interval`-th call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sample_interval` is zero.
+    pub fn new(pipe: P, sample_interval: usize) -> Self {
+        assert!(sample_interval != 0, "The sample interval must not be zero");
+        Self {
+            pipe,
+            sample_interval,
+            stats: ProfileStats::default(),
+        }
+    }
+
+    /// Return the collected statistics.
+    pub fn stats(&self) -> ProfileStats {
+        self.stats
+    }
+
+    /// Clear the collected statistics.
+    pub fn reset_stats(&mut self) {
+        self.stats = ProfileStats::default();
+    }
+
+    /// Return a reference to the wrapped pipe.
+    pub fn inner(&self) -> &P {
+        &self.pipe
+    }
+
+    /// Return a mutable reference to the wrapped pipe.
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.pipe
+    }
+}
+
+impl<P: Pipe> Pipe for Profile<P> {
+    type InputItem = P::InputItem;
+    type OutputItem = P::OutputItem;
+
+    fn next(&mut self, item: P::InputItem) -> P::OutputItem {
+        let measure = self.stats.calls.is_multiple_of(self.sample_interval);
+        self.stats.calls += 1;
+
+        if measure {
+            let start = Instant::now();
+            let item = self.pipe.next(item);
+            let duration = start.elapsed();
+
+            self.stats.measured_calls += 1;
+            self.stats.total += duration;
+            self.stats.max = self.stats.max.max(duration);
+            item
+        } else {
+            self.pipe.next(item)
+        }
+    }
+
+    fn finish(&mut self) -> Vec<P::OutputItem> {
+        self.pipe.finish()
+    }
+}
+
+impl<P: ResetablePipe> ResetablePipe for Profile<P> {
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A handle to the items captured by a [`Probe`](struct.Probe.html).
+///
+/// The handle shares the buffer with its probe, so it can be used to inspect the captured items while the probe is buried inside a pipeline.
+#[derive(Debug)]
+pub struct ProbeHandle<T> {
+    buffer: Rc<RefCell<VecDeque<T>>>,
+}
+
+impl<T> ProbeHandle<T> {
+    /// Return the number of captured items.
+    pub fn len(&self) -> usize {
+        self.buffer.borrow().len()
+    }
+
+    /// Return `true` if no items are captured.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.borrow().is_empty()
+    }
+
+    /// Take all captured items out of the buffer.
+    pub fn take(&self) -> Vec<T> {
+        self.buffer.borrow_mut().drain(..).collect()
+    }
+
+    /// Discard all captured items.
+    pub fn clear(&self) {
+        self.buffer.borrow_mut().clear();
+    }
+}
+
+impl<T: Clone> ProbeHandle<T> {
+    /// Return a copy of the captured items, from the oldest to the newest.
+    pub fn items(&self) -> Vec<T> {
+        self.buffer.borrow().iter().cloned().collect()
+    }
+}
+
+/// A pipe that captures the output items of another pipe.
+///
+/// Every output item of the wrapped pipe is cloned into a shared buffer and passed through unchanged. The captured items can be inspected with the [`ProbeHandle`](struct.ProbeHandle.html) that was created along with the probe. If the probe is bounded, only the latest items are kept. Resetting this pipe resets the wrapped pipe and clears the buffer.
+///
+/// Since the buffer is shared via an `Rc`, a probe can not be sent to another thread. It's mainly intended for tests.
+///
+/// For more information, please see [the documentation of the `probe` method](trait.Pipe.html#method.probe).
+pub struct Probe<P>
+where
+    P: Pipe,
+    P::OutputItem: Clone,
+{
+    pipe: P,
+    buffer: Rc<RefCell<VecDeque<P::OutputItem>>>,
+    limit: Option<usize>,
+}
+
+impl<P> Probe<P>
+where
+    P: Pipe,
+    P::OutputItem: Clone,
+{
+    /// Create a new probe and its handle.
+    ///
+    /// If `limit` is `Some(n)`, only the latest `n` items are kept.
+    pub fn new(pipe: P, limit: Option<usize>) -> (Self, ProbeHandle<P::OutputItem>) {
+        let buffer = Rc::new(RefCell::new(VecDeque::new()));
+        let handle = ProbeHandle {
+            buffer: buffer.clone(),
+        };
+        (
+            Self {
+                pipe,
+                buffer,
+                limit,
+            },
+            handle,
+        )
+    }
+
+    /// Clone an item into the buffer and drop the oldest item if the buffer is full.
+    fn capture(&self, item: &P::OutputItem) {
+        let mut buffer = self.buffer.borrow_mut();
+        if let Some(limit) = self.limit {
+            if buffer.len() >= limit {
+                buffer.pop_front();
+            }
+        }
+        if self.limit != Some(0) {
+            buffer.push_back(item.clone());
+        }
+    }
+}
+
+impl<P> Pipe for Probe<P>
+where
+    P: Pipe,
+    P::OutputItem: Clone,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = P::OutputItem;
+
+    fn next(&mut self, item: P::InputItem) -> P::OutputItem {
+        let item = self.pipe.next(item);
+        self.capture(&item);
+        item
+    }
+
+    fn finish(&mut self) -> Vec<P::OutputItem> {
+        let items = self.pipe.finish();
+        for item in &items {
+            self.capture(item);
+        }
+        items
+    }
+}
+
+impl<P> ResetablePipe for Probe<P>
+where
+    P: ResetablePipe,
+    P::OutputItem: Clone,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+        self.buffer.borrow_mut().clear();
+    }
+}
+
+#[test]
+fn metrics() {
+    use crate::Ditto;
+
+    let values: [f32; 6] = [2.0, 4.0, 4.0, 4.0, 5.0, 7.0];
+    let mut pipe = Ditto::<f32>::default().metrics();
+
+    let snapshot = pipe.snapshot();
+    assert_eq!(0, snapshot.count);
+    assert!(snapshot.min.is_nan());
+    assert!(snapshot.max.is_nan());
+    assert!(snapshot.mean.is_nan());
+    assert!(snapshot.variance.is_nan());
+
+    for value in values.iter() {
+        assert_eq!(*value, pipe.next(*value));
+    }
+
+    let mean: f64 = values.iter().map(|v| *v as f64).sum::<f64>() / values.len() as f64;
+    let variance: f64 = values
+        .iter()
+        .map(|v| (*v as f64 - mean).powi(2))
+        .sum::<f64>()
+        / values.len() as f64;
+
+    let snapshot = pipe.snapshot();
+    assert_eq!(6, snapshot.count);
+    assert_eq!(2.0, snapshot.min);
+    assert_eq!(7.0, snapshot.max);
+    assert!((snapshot.mean - mean).abs() < 1e-12);
+    assert!((snapshot.variance - variance).abs() < 1e-12);
+
+    pipe.reset();
+    assert_eq!(0, pipe.snapshot().count);
+
+    pipe.next(3.0);
+    let snapshot = pipe.snapshot();
+    assert_eq!(1, snapshot.count);
+    assert_eq!(3.0, snapshot.mean);
+    assert_eq!(0.0, snapshot.variance);
+}
+
+#[test]
+fn metrics_stateful_projection() {
+    use crate::Ditto;
+
+    // Collect statistics about the differences between consecutive items.
+    let mut previous = 0.0;
+    let mut pipe = Ditto::<f64>::default().metrics_by(move |item: &f64| {
+        let delta = *item - previous;
+        previous = *item;
+        delta
+    });
+    for i in &[1.0, 3.0, 6.0] {
+        pipe.next(*i);
+    }
+    let snapshot = pipe.snapshot();
+    assert_eq!(3, snapshot.count);
+    assert_eq!(2.0, snapshot.mean);
+    assert_eq!(1.0, snapshot.min);
+    assert_eq!(3.0, snapshot.max);
+}
+
+#[test]
+fn profile() {
+    use crate::Lazy;
+
+    let slow = Lazy::new(|i: u64| {
+        std::thread::sleep(Duration::from_micros(10));
+        i
+    });
+
+    let mut pipe = slow.profile();
+    assert_eq!(None, pipe.stats().mean());
+
+    let mut last_total = Duration::from_secs(0);
+    for i in 0..10 {
+        assert_eq!(i, pipe.next(i));
+        let stats = pipe.stats();
+        assert!(stats.total > last_total);
+        last_total = stats.total;
+    }
+
+    let stats = pipe.stats();
+    assert_eq!(10, stats.calls);
+    assert_eq!(10, stats.measured_calls);
+    assert!(stats.max >= Duration::from_micros(10));
+    assert!(stats.max <= stats.total);
+    assert!(stats.mean().unwrap() >= Duration::from_micros(10));
+
+    pipe.reset();
+    assert_eq!(10, pipe.stats().calls);
+    pipe.reset_stats();
+    assert_eq!(ProfileStats::default(), pipe.stats());
+
+    let mut pipe = Lazy::new(|i: u64| i).profile_sampled(4);
+    for i in 0..10 {
+        pipe.next(i);
+    }
+    let stats = pipe.stats();
+    assert_eq!(10, stats.calls);
+    // Calls 0, 4, and 8 are measured.
+    assert_eq!(3, stats.measured_calls);
+}
+
+#[test]
+fn profile_stats_mean() {
+    // More than `u32::MAX` measured calls are only possible with a 64-bit `usize`.
+    #[cfg(target_pointer_width = "64")]
+    {
+        let stats = ProfileStats {
+            calls: 1 << 33,
+            measured_calls: 1 << 32,
+            total: Duration::from_secs(1 << 33),
+            max: Duration::from_secs(3),
+        };
+        assert_eq!(Some(Duration::from_secs(2)), stats.mean());
+    }
+
+    let stats = ProfileStats {
+        calls: 3,
+        measured_calls: 3,
+        total: Duration::from_millis(10),
+        max: Duration::from_millis(5),
+    };
+    assert_eq!(Some(Duration::new(0, 3_333_333)), stats.mean());
+    assert_eq!(None, ProfileStats::default().mean());
+}
+
+#[test]
+fn probe_in_chain() {
+    use crate::{Counter, Lazy};
+
+    let (probe, handle) = Lazy::new(|i: u32| i * 3).probe();
+    let mut pipe = Counter::new(0u32, 1).compose() >> probe >> Lazy::new(|i: u32| i + 1);
+
+    let output: Vec<u32> = (0..4).map(|_| pipe.next(())).collect();
+    assert_eq!(vec![1, 4, 7, 10], output);
+    assert_eq!(vec![0, 3, 6, 9], handle.items());
+
+    assert_eq!(vec![0, 3, 6, 9], handle.take());
+    assert!(handle.is_empty());
+
+    pipe.next(());
+    assert_eq!(vec![12], handle.items());
+    pipe.reset();
+    assert!(handle.is_empty());
+    pipe.next(());
+    assert_eq!(vec![0], handle.items());
+}
+
+#[test]
+fn probe_bounded() {
+    use crate::Counter;
+
+    let (mut pipe, handle) = Counter::new(0u32, 1).probe_bounded(3);
+    for _ in 0..10 {
+        pipe.next(());
+    }
+    assert_eq!(vec![7, 8, 9], handle.items());
+    assert_eq!(3, handle.len());
+    handle.clear();
+    assert!(handle.is_empty());
+}
+
+#[test]
+fn stats() {
+    use crate::dsp::XorShift;
+
+    let mut rng = XorShift::new(1234);
+    let data: Vec<f64> = (0..10_000)
+        .map(|_| 1e6 + rng.next_f32() as f64 * 10.0)
+        .collect();
+
+    let mut stats: Stats<f64> = Stats::new();
+    let snapshot = data.iter().map(|x| stats.next(*x)).last().unwrap();
+
+    // A two-pass reference computation.
+    let mean = data.iter().sum::<f64>() / data.len() as f64;
+    let variance = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / data.len() as f64;
+    assert_eq!(data.len(), snapshot.count);
+    assert!((snapshot.mean - mean).abs() < 1e-6);
+    assert!((snapshot.variance - variance).abs() < 1e-6);
+    assert_eq!(
+        data.iter().cloned().fold(f64::INFINITY, f64::min),
+        snapshot.min
+    );
+    assert_eq!(
+        data.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        snapshot.max
+    );
+    assert_eq!(snapshot, stats.snapshot());
+
+    stats.reset();
+    assert_eq!(0, stats.snapshot().count);
+    assert!(stats.snapshot().mean.is_nan());
+
+    let snapshot = stats.next(-4.0);
+    assert_eq!(
+        StatsSnapshot {
+            count: 1,
+            min: -4.0,
+            max: -4.0,
+            mean: -4.0,
+            variance: 0.0,
+        },
+        snapshot
+    );
+}
@@ -0,0 +1,257 @@
+use crate::{Connector, Pipe, ResetablePipe};
+use std::any::Any;
+
+/// The stages of a [`Pipeline`](struct.Pipeline.html).
+///
+/// This trait is implemented by [`NamedStage`](struct.NamedStage.html) and by [`Connector`s](struct.Connector.html) of stages. It's used to look up stages by their name and usually doesn't need to be used directly.
+pub trait PipelineStages: ResetablePipe {
+    /// Return the first stage with the given name as a mutable `Any` reference.
+    fn stage_any_mut(&mut self, name: &str) -> Option<&mut dyn Any>;
+
+    /// Append the names of all stages to the vector, in order.
+    fn collect_stage_names<'a>(&'a self, names: &mut Vec<&'a str>);
+}
+
+/// A pipe with a name, the building block of a [`Pipeline`](struct.Pipeline.html).
+pub struct NamedStage<P>
+where
+    P: ResetablePipe + 'static,
+{
+    name: String,
+    pipe: P,
+}
+
+impl<P> NamedStage<P>
+where
+    P: ResetablePipe + 'static,
+{
+    /// Create a new named stage.
+    pub fn new(name: impl Into<String>, pipe: P) -> Self {
+        Self {
+            name: name.into(),
+            pipe,
+        }
+    }
+
+    /// Return the name of the stage.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<P> Pipe for NamedStage<P>
+where
+    P: ResetablePipe + 'static,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = P::OutputItem;
+
+    fn next(&mut self, item: P::InputItem) -> P::OutputItem {
+        self.pipe.next(item)
+    }
+
+    fn finish(&mut self) -> Vec<P::OutputItem> {
+        self.pipe.finish()
+    }
+}
+
+impl<P> ResetablePipe for NamedStage<P>
+where
+    P: ResetablePipe + 'static,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+impl<P> PipelineStages for NamedStage<P>
+where
+    P: ResetablePipe + 'static,
+{
+    fn stage_any_mut(&mut self, name: &str) -> Option<&mut dyn Any> {
+        if self.name == name {
+            Some(&mut self.pipe)
+        } else {
+            None
+        }
+    }
+
+    fn collect_stage_names<'a>(&'a self, names: &mut Vec<&'a str>) {
+        names.push(&self.name);
+    }
+}
+
+impl<P0, P1> PipelineStages for Connector<P0, P1>
+where
+    P0: PipelineStages,
+    P1: PipelineStages<InputItem = P0::OutputItem>,
+{
+    fn stage_any_mut(&mut self, name: &str) -> Option<&mut dyn Any> {
+        let (pipe0, pipe1) = self.parts_mut();
+        match pipe0.stage_any_mut(name) {
+            Some(stage) => Some(stage),
+            None => pipe1.stage_any_mut(name),
+        }
+    }
+
+    fn collect_stage_names<'a>(&'a self, names: &mut Vec<&'a str>) {
+        self.first().collect_stage_names(names);
+        self.second().collect_stage_names(names);
+    }
+}
+
+/// The state of a [`PipelineBuilder`](struct.PipelineBuilder.html) without any stages.
+pub struct NoStages;
+
+/// A builder for a linear pipeline of named stages.
+///
+/// Every call to [`stage`](#method.stage) appends a named pipe to the pipeline. The input item of every stage has to match the output item of the previous one, which is checked at compile time. Once all stages are added, [`build`](#method.build) creates the [`Pipeline`](struct.Pipeline.html).
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipeline = PipelineBuilder::new()
+///     .stage("counter", Counter::new(0.0f32, 1.0))
+///     .stage("gain", ScaleOffset::new(2.0, 0.0))
+///     .build();
+///
+/// assert_eq!(0.0, pipeline.next(()));
+/// assert_eq!(2.0, pipeline.next(()));
+///
+/// pipeline.stage_mut::<ScaleOffset<f32>>("gain").unwrap().set_scale(-1.0);
+/// assert_eq!(-2.0, pipeline.next(()));
+/// ```
+pub struct PipelineBuilder<S> {
+    stages: S,
+}
+
+impl PipelineBuilder<NoStages> {
+    /// Create a new builder without any stages.
+    pub fn new() -> Self {
+        Self { stages: NoStages }
+    }
+
+    /// Add the first stage to the pipeline.
+    pub fn stage<P>(self, name: impl Into<String>, pipe: P) -> PipelineBuilder<NamedStage<P>>
+    where
+        P: ResetablePipe + 'static,
+    {
+        PipelineBuilder {
+            stages: NamedStage::new(name, pipe),
+        }
+    }
+}
+
+impl Default for PipelineBuilder<NoStages> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: PipelineStages> PipelineBuilder<S> {
+    /// Append a stage to the pipeline.
+    pub fn stage<P>(
+        self,
+        name: impl Into<String>,
+        pipe: P,
+    ) -> PipelineBuilder<Connector<S, NamedStage<P>>>
+    where
+        P: ResetablePipe<InputItem = S::OutputItem> + 'static,
+    {
+        PipelineBuilder {
+            stages: Connector::new(self.stages, NamedStage::new(name, pipe)),
+        }
+    }
+
+    /// Create the pipeline.
+    pub fn build(self) -> Pipeline<S> {
+        Pipeline {
+            stages: self.stages,
+        }
+    }
+}
+
+/// A linear pipeline of named stages.
+///
+/// The pipeline passes every input item through its stages in order and returns the output of the last stage. Stages can be inspected and modified by their name, which is useful for debugging larger pipelines or to adjust parameters at runtime. Resetting the pipeline resets all stages.
+///
+/// Pipelines are created with a [`PipelineBuilder`](struct.PipelineBuilder.html).
+pub struct Pipeline<S: PipelineStages> {
+    stages: S,
+}
+
+impl<S: PipelineStages> Pipeline<S> {
+    /// Return the stage with the given name, if it exists and has the type `T`.
+    ///
+    /// If there are multiple stages with the same name, the first one is returned.
+    pub fn stage_mut<T: 'static>(&mut self, name: &str) -> Option<&mut T> {
+        self.stages
+            .stage_any_mut(name)
+            .and_then(|stage| stage.downcast_mut())
+    }
+
+    /// Return the names of all stages, in order.
+    pub fn stage_names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.stages.collect_stage_names(&mut names);
+        names
+    }
+}
+
+impl<S: PipelineStages> Pipe for Pipeline<S> {
+    type InputItem = S::InputItem;
+    type OutputItem = S::OutputItem;
+
+    fn next(&mut self, item: S::InputItem) -> S::OutputItem {
+        self.stages.next(item)
+    }
+
+    fn finish(&mut self) -> Vec<S::OutputItem> {
+        self.stages.finish()
+    }
+}
+
+impl<S: PipelineStages> ResetablePipe for Pipeline<S> {
+    fn reset(&mut self) {
+        self.stages.reset();
+    }
+}
+
+#[test]
+fn pipeline() {
+    use crate::dsp::Square;
+    use crate::{Clamp, ScaleOffset};
+
+    let mut pipeline = PipelineBuilder::new()
+        .stage("osc", Square::from_increment(0.25))
+        .stage("gain", ScaleOffset::new(0.5, 0.0))
+        .stage("clamp", Clamp::new(-1.0, 1.0))
+        .build();
+    assert_eq!(vec!["osc", "gain", "clamp"], pipeline.stage_names());
+
+    let outputs: Vec<f32> = (0..4).map(|_| pipeline.next(())).collect();
+    assert_eq!(vec![0.5, 0.5, -0.5, -0.5], outputs);
+
+    // Tweak the gain and the pulse width mid-run.
+    pipeline
+        .stage_mut::<ScaleOffset<f32>>("gain")
+        .unwrap()
+        .set_scale(4.0);
+    pipeline
+        .stage_mut::<Square>("osc")
+        .unwrap()
+        .set_pulse_width(0.75);
+    let outputs: Vec<f32> = (0..4).map(|_| pipeline.next(())).collect();
+    assert_eq!(vec![1.0, 1.0, 1.0, -1.0], outputs);
+
+    // Wrong names or types aren't found.
+    assert!(pipeline.stage_mut::<Square>("gain").is_none());
+    assert!(pipeline.stage_mut::<Square>("filter").is_none());
+
+    // Resetting restores the initial gain, but the pulse width is kept.
+    pipeline.reset();
+    let outputs: Vec<f32> = (0..4).map(|_| pipeline.next(())).collect();
+    assert_eq!(vec![0.5, 0.5, 0.5, -0.5], outputs);
+}
@@ -0,0 +1,274 @@
+use crate::{ConsumeResult, Pipe, ResetablePipe};
+
+/// A source of items that is polled by its downstream.
+///
+/// Pipes are driven by pushing input items into them. Some stages, like resamplers or parsers, need to decide themselves when they need more input. They can pull their input items from a producer instead.
+///
+/// Every pipe with the input item `()` and an `Option` as the output item, like the [`SliceProducer`](struct.SliceProducer.html), is a producer. Returning `None` signals the end of the stream.
+pub trait Producer {
+    /// The type of the produced items.
+    type Item;
+
+    /// Produce the next item, or `None` if the producer is exhausted.
+    fn produce(&mut self) -> Option<Self::Item>;
+}
+
+impl<P, T> Producer for P
+where
+    P: Pipe<InputItem = (), OutputItem = Option<T>>,
+{
+    type Item = T;
+
+    fn produce(&mut self) -> Option<T> {
+        self.next(())
+    }
+}
+
+/// A sink of items.
+///
+/// Every pipe that returns a [`ConsumeResult`](enum.ConsumeResult.html), like the [`SliceConsumer`](struct.SliceConsumer.html), is a consumer.
+pub trait Consumer {
+    /// The type of the consumed items.
+    type Item;
+
+    /// Consume an item and report whether it could be stored.
+    fn consume(&mut self, item: Self::Item) -> ConsumeResult;
+}
+
+impl<P> Consumer for P
+where
+    P: Pipe<OutputItem = ConsumeResult>,
+{
+    type Item = P::InputItem;
+
+    fn consume(&mut self, item: P::InputItem) -> ConsumeResult {
+        self.next(item)
+    }
+}
+
+/// Move all items from a producer through a pipe into a consumer.
+///
+/// The loop runs until the producer is exhausted or the consumer is full. If the producer is exhausted, the pipe is finished and the items it flushes are consumed too. The number of consumed items is returned.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// const INPUT: &[u8] = &[1, 2, 3];
+/// let mut output: [u8; 3] = [0; 3];
+///
+/// let consumed = drive(
+///     &mut SliceProducer::new(INPUT),
+///     &mut Lazy::new(|i: &u8| i * 2),
+///     &mut SliceConsumer::new(&mut output),
+/// );
+/// assert_eq!(3, consumed);
+/// assert_eq!([2, 4, 6], output);
+/// ```
+pub fn drive<R, P, C>(producer: &mut R, pipe: &mut P, consumer: &mut C) -> usize
+where
+    R: Producer,
+    P: Pipe<InputItem = R::Item>,
+    C: Consumer<Item = P::OutputItem>,
+{
+    let mut consumed = 0;
+    while let Some(item) = producer.produce() {
+        if consumer.consume(pipe.next(item)) == ConsumeResult::Full {
+            return consumed;
+        }
+        consumed += 1;
+    }
+    for item in pipe.finish() {
+        if consumer.consume(item) == ConsumeResult::Full {
+            break;
+        }
+        consumed += 1;
+    }
+    consumed
+}
+
+/// A processing stage that pulls its input items from an upstream producer.
+///
+/// In contrast to a pipe, a pull stage decides how many input items it needs to produce an output item. It's turned into a producer by wrapping it and its upstream in a [`Pulled`](struct.Pulled.html) adapter.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// /// A stage that sums up pairs of items.
+/// struct PairSum;
+///
+/// impl PullStage for PairSum {
+///     type InputItem = u8;
+///     type OutputItem = u8;
+///
+///     fn pull<U>(&mut self, upstream: &mut U) -> Option<u8>
+///     where
+///         U: Producer<Item = u8>,
+///     {
+///         Some(upstream.produce()? + upstream.produce()?)
+///     }
+/// }
+///
+/// let mut producer = Pulled::new(Counter::new(0u8, 1).connect(Lazy::new(Some)), PairSum);
+/// assert_eq!(Some(1), producer.next(()));
+/// assert_eq!(Some(5), producer.next(()));
+/// ```
+pub trait PullStage {
+    /// The type of the items pulled from the upstream producer.
+    type InputItem;
+    /// The type of the produced items.
+    type OutputItem;
+
+    /// Pull as many items from the upstream producer as needed and produce the next output item.
+    ///
+    /// Returning `None` signals the end of the stream, which usually happens if the upstream producer is exhausted.
+    fn pull<U>(&mut self, upstream: &mut U) -> Option<Self::OutputItem>
+    where
+        U: Producer<Item = Self::InputItem>;
+
+    /// Reset the stage to its initial state.
+    ///
+    /// The default implementation does nothing, which is correct for stateless stages.
+    fn reset(&mut self) {}
+}
+
+/// A producer that drives a pull stage with an upstream producer.
+///
+/// Every call to `next` lets the stage pull its input items from the upstream producer and returns the stage's output item. Resetting this pipe resets both the upstream producer and the stage.
+///
+/// Since producers can't be finished, finishing this pipe doesn't finish the upstream producer, even if it's a pipe. Items that the upstream pipe holds back are therefore lost.
+///
+/// For more information, please see [the documentation of the `PullStage` trait](trait.PullStage.html).
+pub struct Pulled<U, S>
+where
+    U: Producer<Item = S::InputItem>,
+    S: PullStage,
+{
+    upstream: U,
+    stage: S,
+}
+
+impl<U, S> Pulled<U, S>
+where
+    U: Producer<Item = S::InputItem>,
+    S: PullStage,
+{
+    /// Create a new producer from the upstream producer and the pull stage.
+    pub fn new(upstream: U, stage: S) -> Self {
+        Self { upstream, stage }
+    }
+
+    /// Return the pull stage.
+    pub fn stage(&self) -> &S {
+        &self.stage
+    }
+
+    /// Return the pull stage mutably.
+    pub fn stage_mut(&mut self) -> &mut S {
+        &mut self.stage
+    }
+}
+
+impl<U, S> Pipe for Pulled<U, S>
+where
+    U: Producer<Item = S::InputItem>,
+    S: PullStage,
+{
+    type InputItem = ();
+    type OutputItem = Option<S::OutputItem>;
+
+    fn next(&mut self, _: ()) -> Option<S::OutputItem> {
+        self.stage.pull(&mut self.upstream)
+    }
+}
+
+impl<U, S> ResetablePipe for Pulled<U, S>
+where
+    U: Producer<Item = S::InputItem> + ResetablePipe,
+    S: PullStage,
+{
+    fn reset(&mut self) {
+        ResetablePipe::reset(&mut self.upstream);
+        self.stage.reset();
+    }
+}
+
+#[test]
+fn pull_downsampler() {
+    use crate::{Lazy, SliceConsumer, SliceProducer};
+
+    /// A 2:1 downsampler that averages pairs of samples and drops an incomplete pair.
+    struct Downsample {
+        pairs: usize,
+    }
+
+    impl PullStage for Downsample {
+        type InputItem = f32;
+        type OutputItem = f32;
+
+        fn pull<U>(&mut self, upstream: &mut U) -> Option<f32>
+        where
+            U: Producer<Item = f32>,
+        {
+            let first = upstream.produce()?;
+            let second = upstream.produce()?;
+            self.pairs += 1;
+            Some((first + second) / 2.0)
+        }
+
+        fn reset(&mut self) {
+            self.pairs = 0;
+        }
+    }
+
+    let input: Vec<f32> = (0..9).map(|i| i as f32).collect();
+    let mut output: Vec<f32> = vec![0.0; 8];
+
+    let upstream = SliceProducer::new(&input).compose() >> Lazy::new(|i: Option<&f32>| i.cloned());
+    let mut producer = Pulled::new(upstream, Downsample { pairs: 0 });
+    let mut consumer = SliceConsumer::new(&mut output);
+
+    let consumed = drive(
+        &mut producer,
+        &mut Lazy::new(|i: f32| i * 2.0),
+        &mut consumer,
+    );
+    assert_eq!(4, consumed);
+    assert_eq!(4, producer.stage().pairs);
+
+    // Run it again until the consumer is full.
+    producer.reset();
+    assert_eq!(0, producer.stage().pairs);
+    assert_eq!(
+        4,
+        drive(&mut producer, &mut Lazy::new(|i: f32| -i), &mut consumer)
+    );
+    assert_eq!(8, consumer.written());
+    assert_eq!(ConsumeResult::Full, Consumer::consume(&mut consumer, 0.0));
+
+    assert_eq!(vec![1.0, 5.0, 9.0, 13.0, -0.5, -2.5, -4.5, -6.5], output);
+}
+
+#[test]
+fn drive_flushes_finished_pipe() {
+    use crate::{Buffer, Lazy, SliceConsumer, SliceProducer};
+
+    const INPUT: &[u8] = &[1, 2, 3];
+    let mut output: Vec<Option<u8>> = vec![None; 4];
+
+    // The buffer holds back manually pushed items, which are flushed at the end of the stream.
+    let mut buffer = Buffer::new();
+    buffer.push(0).unwrap();
+    let mut pipe = Lazy::new(|i: &u8| Some(*i)).compose() >> buffer;
+
+    let consumed = drive(
+        &mut SliceProducer::new(INPUT),
+        &mut pipe,
+        &mut SliceConsumer::new(&mut output),
+    );
+    assert_eq!(4, consumed);
+    assert_eq!(vec![Some(0), Some(1), Some(2), Some(3)], output);
+}
@@ -0,0 +1,278 @@
+use crate::{Pipe, ResetablePipe};
+
+/// A pipe that only passes every k-th output item of another pipe.
+///
+/// For more information, please see [the documentation of the `decimate` method](trait.Pipe.html#method.decimate).
+pub struct Decimate<P>
+where
+    P: Pipe,
+{
+    pipe: P,
+    factor: usize,
+    phase: usize,
+}
+
+impl<P: Pipe> Decimate<P> {
+    /// Create a new decimating pipe.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is zero.
+    pub fn new(pipe: P, factor: usize) -> Self {
+        assert!(factor > 0, "The decimation factor must not be zero");
+        Self {
+            pipe,
+            factor,
+            phase: 0,
+        }
+    }
+
+    /// Return the decimation factor.
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Pass the item if it's the first one of the current period.
+    fn decimate(&mut self, item: P::OutputItem) -> Option<P::OutputItem> {
+        let pass = self.phase == 0;
+        self.phase = (self.phase + 1) % self.factor;
+        if pass {
+            Some(item)
+        } else {
+            None
+        }
+    }
+}
+
+impl<P: Pipe> Pipe for Decimate<P> {
+    type InputItem = P::InputItem;
+    type OutputItem = Option<P::OutputItem>;
+
+    fn next(&mut self, item: P::InputItem) -> Option<P::OutputItem> {
+        let item = self.pipe.next(item);
+        self.decimate(item)
+    }
+
+    fn finish(&mut self) -> Vec<Option<P::OutputItem>> {
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(|item| self.decimate(item))
+            .collect()
+    }
+}
+
+impl<P: ResetablePipe> ResetablePipe for Decimate<P> {
+    fn reset(&mut self) {
+        self.pipe.reset();
+        self.phase = 0;
+    }
+}
+
+/// A value that can be linearly interpolated.
+pub trait Interpolate: Clone {
+    /// Interpolate between `self` and `other`.
+    ///
+    /// A factor of `0.0` returns `self` and a factor of `1.0` returns `other`.
+    fn interpolate(&self, other: &Self, factor: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &f32, factor: f32) -> f32 {
+        self + (other - self) * factor
+    }
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, other: &f64, factor: f32) -> f64 {
+        self + (other - self) * factor as f64
+    }
+}
+
+/// A pipe that converts a sparse stream of values into a continuous one.
+///
+/// The input item of this pipe is an optional value, for example the output item of a [decimated](trait.Pipe.html#method.decimate) pipe. By default, the upsampler holds the last received value until the next one arrives (zero-order hold) and returns the initial value until the first value arrives.
+///
+/// If it was created with [`linear`](#method.linear), the upsampler glides linearly from the previous value to the last received value within `factor` calls instead. This introduces a delay of one period: When a value arrives, the previous value is returned and the received value is reached `factor` calls later. If a value arrives before the previous glide is finished, the new glide starts where the old one was interrupted.
+///
+/// Decimating and upsampling with the same factor is well aligned: If the decimator passes an item on every k-th call, starting with the first one, the hold mode returns that item for this call and the following `k - 1` calls.
+///
+/// Resetting this pipe restores the initial value.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut hold = Counter::new(0.0, 1.0).decimate(4).compose() >> Upsample::new(4, 0.0);
+/// let signal: Vec<f32> = (0..8).map(|_| hold.next(())).collect();
+/// assert_eq!(vec![0.0, 0.0, 0.0, 0.0, 4.0, 4.0, 4.0, 4.0], signal);
+///
+/// let mut linear = Counter::new(0.0, 1.0).decimate(4).compose() >> Upsample::linear(4, 0.0);
+/// let signal: Vec<f32> = (0..12).map(|_| linear.next(())).collect();
+/// assert_eq!(
+///     vec![0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0],
+///     signal
+/// );
+/// ```
+pub struct Upsample<T: Clone> {
+    factor: usize,
+    initial: T,
+    previous: T,
+    last: T,
+    position: usize,
+    interpolate: Option<fn(&T, &T, f32) -> T>,
+}
+
+impl<T: Clone> Upsample<T> {
+    /// Create a new upsampler that holds the last received value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is zero.
+    pub fn new(factor: usize, initial: T) -> Self {
+        Self::with_interpolation(factor, initial, None)
+    }
+
+    fn with_interpolation(
+        factor: usize,
+        initial: T,
+        interpolate: Option<fn(&T, &T, f32) -> T>,
+    ) -> Self {
+        assert!(factor > 0, "The upsampling factor must not be zero");
+        Self {
+            factor,
+            previous: initial.clone(),
+            last: initial.clone(),
+            initial,
+            position: 0,
+            interpolate,
+        }
+    }
+
+    /// Return the upsampling factor.
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Calculate the value for the current position.
+    fn current(&self) -> T {
+        match self.interpolate {
+            Some(interpolate) if self.position < self.factor => interpolate(
+                &self.previous,
+                &self.last,
+                self.position as f32 / self.factor as f32,
+            ),
+            _ => self.last.clone(),
+        }
+    }
+}
+
+impl<T: Interpolate> Upsample<T> {
+    /// Create a new upsampler that interpolates linearly between the received values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is zero.
+    pub fn linear(factor: usize, initial: T) -> Self {
+        Self::with_interpolation(factor, initial, Some(T::interpolate))
+    }
+}
+
+impl<T: Clone> Pipe for Upsample<T> {
+    type InputItem = Option<T>;
+    type OutputItem = T;
+
+    fn next(&mut self, item: Option<T>) -> T {
+        if let Some(item) = item {
+            self.previous = self.current();
+            self.last = item;
+            self.position = 0;
+        }
+
+        let output = self.current();
+        if self.position < self.factor {
+            self.position += 1;
+        }
+        output
+    }
+}
+
+impl<T: Clone> ResetablePipe for Upsample<T> {
+    fn reset(&mut self) {
+        self.previous = self.initial.clone();
+        self.last = self.initial.clone();
+        self.position = 0;
+    }
+}
+
+#[test]
+fn decimate_phase() {
+    use crate::Counter;
+
+    let mut pipe = Counter::new(0u32, 1).decimate(3);
+    let items: Vec<Option<u32>> = (0..7).map(|_| pipe.next(())).collect();
+    assert_eq!(
+        vec![Some(0), None, None, Some(3), None, None, Some(6)],
+        items
+    );
+
+    pipe.reset();
+    assert_eq!(Some(0), pipe.next(()));
+    assert_eq!(None, pipe.next(()));
+
+    let mut pipe = Counter::new(0u32, 1).decimate(1);
+    assert_eq!(Some(0), pipe.next(()));
+    assert_eq!(Some(1), pipe.next(()));
+}
+
+#[test]
+fn upsample_round_trip() {
+    use crate::Counter;
+
+    let mut pipe = Counter::new(0u32, 1).decimate(3).compose() >> Upsample::new(3, 100);
+    let items: Vec<u32> = (0..9).map(|_| pipe.next(())).collect();
+    assert_eq!(vec![0, 0, 0, 3, 3, 3, 6, 6, 6], items);
+
+    // The initial value is held until the first value arrives.
+    let mut upsample = Upsample::new(2, 'a');
+    assert_eq!('a', upsample.next(None));
+    assert_eq!('b', upsample.next(Some('b')));
+    assert_eq!('b', upsample.next(None));
+    assert_eq!('b', upsample.next(None));
+    upsample.reset();
+    assert_eq!('a', upsample.next(None));
+}
+
+#[test]
+fn upsample_linear() {
+    let mut upsample = Upsample::linear(4, 1.0f32);
+    let inputs = vec![
+        Some(3.0),
+        None,
+        None,
+        None,
+        Some(-1.0),
+        None,
+        Some(0.0),
+        None,
+        None,
+        None,
+        None,
+    ];
+    let outputs: Vec<f32> = inputs.into_iter().map(|i| upsample.next(i)).collect();
+    assert_eq!(
+        vec![1.0, 1.5, 2.0, 2.5, 3.0, 2.0, 1.0, 0.75, 0.5, 0.25, 0.0],
+        outputs
+    );
+
+    upsample.reset();
+    assert_eq!(1.0, upsample.next(Some(2.0)));
+    assert_eq!(1.25, upsample.next(None));
+
+    let mut upsample = Upsample::linear(2, 0.0f64);
+    assert_eq!(0.0, upsample.next(Some(1.0)));
+    assert_eq!(0.5, upsample.next(None));
+    assert_eq!(1.0, upsample.next(None));
+    assert_eq!(1.0, upsample.next(None));
+}
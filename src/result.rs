@@ -0,0 +1,684 @@
+use crate::{Pipe, ResetablePipe};
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+
+/// A pipe that turns the optional output items of another pipe into results.
+///
+/// For more information, please see [the documentation of the `ok_or` method](trait.Pipe.html#method.ok_or).
+pub struct OkOr<P, E>
+where
+    P: Pipe,
+    E: Clone,
+{
+    pipe: P,
+    error: E,
+}
+
+impl<P, E> OkOr<P, E>
+where
+    P: Pipe,
+    E: Clone,
+{
+    /// Create a new converting pipe.
+    pub fn new(pipe: P, error: E) -> Self {
+        Self { pipe, error }
+    }
+}
+
+impl<P, T, E> Pipe for OkOr<P, E>
+where
+    P: Pipe<OutputItem = Option<T>>,
+    E: Clone,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = Result<T, E>;
+
+    fn next(&mut self, item: P::InputItem) -> Result<T, E> {
+        match self.pipe.next(item) {
+            Some(item) => Ok(item),
+            None => Err(self.error.clone()),
+        }
+    }
+
+    fn finish(&mut self) -> Vec<Result<T, E>> {
+        let error = &self.error;
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(|item| item.ok_or_else(|| error.clone()))
+            .collect()
+    }
+}
+
+impl<P, T, E> ResetablePipe for OkOr<P, E>
+where
+    P: ResetablePipe<OutputItem = Option<T>>,
+    E: Clone,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A pipe that turns the result output items of another pipe into optional items.
+///
+/// Every error is passed to a callback before it is discarded. Note that resetting this pipe only resets the wrapped pipe, not the state of the callback.
+///
+/// For more information, please see [the documentation of the `ok` method](trait.Pipe.html#method.ok) and [the `ok_with` method](trait.Pipe.html#method.ok_with).
+pub struct ResultOk<P, F>
+where
+    P: Pipe,
+{
+    pipe: P,
+    callback: F,
+}
+
+impl<P: Pipe, F> ResultOk<P, F> {
+    /// Create a new converting pipe.
+    pub fn new(pipe: P, callback: F) -> Self {
+        Self { pipe, callback }
+    }
+
+    /// Convert an output item of the wrapped pipe and pass its error to the callback.
+    fn handle<T, E>(&mut self, item: Result<T, E>) -> Option<T>
+    where
+        F: FnMut(E),
+    {
+        match item {
+            Ok(item) => Some(item),
+            Err(error) => {
+                (self.callback)(error);
+                None
+            }
+        }
+    }
+}
+
+impl<P, T, E, F> Pipe for ResultOk<P, F>
+where
+    P: Pipe<OutputItem = Result<T, E>>,
+    F: FnMut(E),
+{
+    type InputItem = P::InputItem;
+    type OutputItem = Option<T>;
+
+    fn next(&mut self, item: P::InputItem) -> Option<T> {
+        let item = self.pipe.next(item);
+        self.handle(item)
+    }
+
+    fn finish(&mut self) -> Vec<Option<T>> {
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(|item| self.handle(item))
+            .collect()
+    }
+}
+
+impl<P, T, E, F> ResetablePipe for ResultOk<P, F>
+where
+    P: ResetablePipe<OutputItem = Result<T, E>>,
+    F: FnMut(E),
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A pipe that turns `Option<Result<T, E>>` output items into `Result<Option<T>, E>` items.
+///
+/// For more information, please see [the documentation of the `transpose_option` method](trait.Pipe.html#method.transpose_option).
+pub struct TransposeOption<P>
+where
+    P: Pipe,
+{
+    pipe: P,
+}
+
+impl<P: Pipe> TransposeOption<P> {
+    /// Create a new transposing pipe.
+    pub fn new(pipe: P) -> Self {
+        Self { pipe }
+    }
+}
+
+impl<P, T, E> Pipe for TransposeOption<P>
+where
+    P: Pipe<OutputItem = Option<Result<T, E>>>,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = Result<Option<T>, E>;
+
+    fn next(&mut self, item: P::InputItem) -> Result<Option<T>, E> {
+        self.pipe.next(item).transpose()
+    }
+
+    fn finish(&mut self) -> Vec<Result<Option<T>, E>> {
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(Option::transpose)
+            .collect()
+    }
+}
+
+impl<P, T, E> ResetablePipe for TransposeOption<P>
+where
+    P: ResetablePipe<OutputItem = Option<Result<T, E>>>,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A pipe that turns `Result<Option<T>, E>` output items into `Option<Result<T, E>>` items.
+///
+/// For more information, please see [the documentation of the `transpose_result` method](trait.Pipe.html#method.transpose_result).
+pub struct TransposeResult<P>
+where
+    P: Pipe,
+{
+    pipe: P,
+}
+
+impl<P: Pipe> TransposeResult<P> {
+    /// Create a new transposing pipe.
+    pub fn new(pipe: P) -> Self {
+        Self { pipe }
+    }
+}
+
+impl<P, T, E> Pipe for TransposeResult<P>
+where
+    P: Pipe<OutputItem = Result<Option<T>, E>>,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = Option<Result<T, E>>;
+
+    fn next(&mut self, item: P::InputItem) -> Option<Result<T, E>> {
+        self.pipe.next(item).transpose()
+    }
+
+    fn finish(&mut self) -> Vec<Option<Result<T, E>>> {
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(Result::transpose)
+            .collect()
+    }
+}
+
+impl<P, T, E> ResetablePipe for TransposeResult<P>
+where
+    P: ResetablePipe<OutputItem = Result<Option<T>, E>>,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A pipe that connects a fallible pipe to another pipe.
+///
+/// The input item of this connector is the input item of `P0`. If `P0` returns `Ok(value)`, the value is fed into `P1` and its output item is returned as `Ok`. If `P0` returns an error, `P1` is not invoked at all and the error is returned unchanged.
+///
+/// For more information, please see [the documentation of the `try_connect` method](trait.Pipe.html#method.try_connect).
+pub struct TryConnector<P0, P1>
+where
+    P0: Pipe,
+    P1: Pipe,
+{
+    pipe0: P0,
+    pipe1: P1,
+}
+
+impl<P0, P1> TryConnector<P0, P1>
+where
+    P0: Pipe,
+    P1: Pipe,
+{
+    /// Create a new fallible connector with the two pipes.
+    pub fn new(pipe0: P0, pipe1: P1) -> Self {
+        TryConnector { pipe0, pipe1 }
+    }
+}
+
+impl<P0, P1, M, E> Pipe for TryConnector<P0, P1>
+where
+    P0: Pipe<OutputItem = Result<M, E>>,
+    P1: Pipe<InputItem = M>,
+{
+    type InputItem = P0::InputItem;
+    type OutputItem = Result<P1::OutputItem, E>;
+
+    fn next(&mut self, input: P0::InputItem) -> Result<P1::OutputItem, E> {
+        let pipe1 = &mut self.pipe1;
+        self.pipe0.next(input).map(|item| pipe1.next(item))
+    }
+
+    fn finish(&mut self) -> Vec<Result<P1::OutputItem, E>> {
+        let pipe1 = &mut self.pipe1;
+        let mut items: Vec<Result<P1::OutputItem, E>> = self
+            .pipe0
+            .finish()
+            .into_iter()
+            .map(|item| item.map(|item| pipe1.next(item)))
+            .collect();
+        items.extend(self.pipe1.finish().into_iter().map(Ok));
+        items
+    }
+}
+
+impl<P0, P1, M, E> ResetablePipe for TryConnector<P0, P1>
+where
+    P0: ResetablePipe<OutputItem = Result<M, E>>,
+    P1: ResetablePipe<InputItem = M>,
+{
+    fn reset(&mut self) {
+        self.pipe0.reset();
+        self.pipe1.reset();
+    }
+}
+
+/// A pipe that maps the errors of another pipe's result output items.
+///
+/// For more information, please see [the documentation of the `map_err` method](trait.Pipe.html#method.map_err).
+pub struct MapErr<P, F>
+where
+    P: Pipe,
+{
+    pipe: P,
+    function: F,
+}
+
+impl<P: Pipe, F> MapErr<P, F> {
+    /// Create a new error mapping pipe.
+    pub fn new(pipe: P, function: F) -> Self {
+        Self { pipe, function }
+    }
+}
+
+impl<P, T, E, E2, F> Pipe for MapErr<P, F>
+where
+    P: Pipe<OutputItem = Result<T, E>>,
+    F: FnMut(E) -> E2,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = Result<T, E2>;
+
+    fn next(&mut self, item: P::InputItem) -> Result<T, E2> {
+        self.pipe.next(item).map_err(&mut self.function)
+    }
+
+    fn finish(&mut self) -> Vec<Result<T, E2>> {
+        let function = &mut self.function;
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(|item| item.map_err(&mut *function))
+            .collect()
+    }
+}
+
+impl<P, T, E, E2, F> ResetablePipe for MapErr<P, F>
+where
+    P: ResetablePipe<OutputItem = Result<T, E>>,
+    F: FnMut(E) -> E2,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A pipe that converts the errors of another pipe's result output items using `From`.
+///
+/// For more information, please see [the documentation of the `err_into` method](trait.Pipe.html#method.err_into).
+pub struct ErrInto<P, E2>
+where
+    P: Pipe,
+{
+    pipe: P,
+    error: PhantomData<E2>,
+}
+
+impl<P: Pipe, E2> ErrInto<P, E2> {
+    /// Create a new error converting pipe.
+    pub fn new(pipe: P) -> Self {
+        Self {
+            pipe,
+            error: PhantomData,
+        }
+    }
+}
+
+impl<P, T, E, E2> Pipe for ErrInto<P, E2>
+where
+    P: Pipe<OutputItem = Result<T, E>>,
+    E2: From<E>,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = Result<T, E2>;
+
+    fn next(&mut self, item: P::InputItem) -> Result<T, E2> {
+        self.pipe.next(item).map_err(E2::from)
+    }
+
+    fn finish(&mut self) -> Vec<Result<T, E2>> {
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(|item| item.map_err(E2::from))
+            .collect()
+    }
+}
+
+impl<P, T, E, E2> ResetablePipe for ErrInto<P, E2>
+where
+    P: ResetablePipe<OutputItem = Result<T, E>>,
+    E2: From<E>,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A pipe that converts the output items of another pipe using `TryFrom`.
+///
+/// For more information, please see [the documentation of the `try_convert` method](trait.Pipe.html#method.try_convert).
+pub struct TryConvert<P, U>
+where
+    P: Pipe,
+    U: TryFrom<P::OutputItem>,
+{
+    pipe: P,
+    output: PhantomData<U>,
+}
+
+impl<P, U> TryConvert<P, U>
+where
+    P: Pipe,
+    U: TryFrom<P::OutputItem>,
+{
+    /// Create a new fallible converting pipe.
+    pub fn new(pipe: P) -> Self {
+        Self {
+            pipe,
+            output: PhantomData,
+        }
+    }
+}
+
+impl<P, U> Pipe for TryConvert<P, U>
+where
+    P: Pipe,
+    U: TryFrom<P::OutputItem>,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = Result<U, U::Error>;
+
+    fn next(&mut self, item: P::InputItem) -> Result<U, U::Error> {
+        U::try_from(self.pipe.next(item))
+    }
+
+    fn finish(&mut self) -> Vec<Result<U, U::Error>> {
+        self.pipe.finish().into_iter().map(U::try_from).collect()
+    }
+}
+
+impl<P, U> ResetablePipe for TryConvert<P, U>
+where
+    P: ResetablePipe,
+    U: TryFrom<P::OutputItem>,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A pipe that retries a fallible pipe.
+///
+/// If the wrapped pipe returns an error, it's invoked again with a clone of the same input item, up to `retries` times. If all attempts fail, the last error is returned. Before every retry, the hook is called with the number of the failed attempt (starting at 1) and the error, which can be used to log the error or to sleep before the next attempt.
+///
+/// Since the input item is moved into the wrapped pipe, it has to be cloned for every attempt that may be followed by a retry. The last possible attempt receives the original input item, so if `retries` is zero, the input item is never cloned.
+///
+/// Note that resetting this pipe only resets the wrapped pipe, not the state of the hook. When this pipe is finished, the remaining items of the wrapped pipe are returned as they are, since there are no input items to retry them with.
+///
+/// For more information, please see [the documentation of the `retry` method](trait.Pipe.html#method.retry).
+pub struct Retry<P, F>
+where
+    P: Pipe,
+{
+    pipe: P,
+    retries: usize,
+    hook: F,
+}
+
+impl<P: Pipe, F> Retry<P, F> {
+    /// Create a new retrying pipe.
+    pub fn new(pipe: P, retries: usize, hook: F) -> Self {
+        Self {
+            pipe,
+            retries,
+            hook,
+        }
+    }
+}
+
+impl<P, T, E, F> Pipe for Retry<P, F>
+where
+    P: Pipe<OutputItem = Result<T, E>>,
+    P::InputItem: Clone,
+    F: FnMut(usize, &E),
+{
+    type InputItem = P::InputItem;
+    type OutputItem = Result<T, E>;
+
+    fn next(&mut self, item: P::InputItem) -> Result<T, E> {
+        for attempt in 1..=self.retries {
+            match self.pipe.next(item.clone()) {
+                Ok(output) => return Ok(output),
+                Err(error) => (self.hook)(attempt, &error),
+            }
+        }
+        self.pipe.next(item)
+    }
+
+    fn finish(&mut self) -> Vec<Result<T, E>> {
+        self.pipe.finish()
+    }
+}
+
+impl<P, T, E, F> ResetablePipe for Retry<P, F>
+where
+    P: ResetablePipe<OutputItem = Result<T, E>>,
+    P::InputItem: Clone,
+    F: FnMut(usize, &E),
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// The default hook of the [`Retry`](struct.Retry.html) pipe, which does nothing.
+pub(crate) fn ignore_error<E>(_: usize, _: &E) {}
+
+#[test]
+fn option_result_chain() {
+    use crate::{Ditto, Lazy, SliceProducer};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct ReadError;
+
+    let data: Vec<Result<u8, ReadError>> = vec![Ok(1), Err(ReadError), Ok(2)];
+
+    let mut errors: usize = 0;
+    let mut pipe = SliceProducer::new(&data)
+        .connect(Lazy::new(|item: Option<&Result<u8, ReadError>>| {
+            item.cloned()
+        }))
+        .transpose_option()
+        .ok_with(|_| errors += 1)
+        .unwrap_or(None);
+
+    let items: Vec<Option<u8>> = (0..4).map(|_| pipe.next(())).collect();
+    assert_eq!(vec![Some(1), None, Some(2), None], items);
+
+    pipe.reset();
+    assert_eq!(Some(1), pipe.next(()));
+    assert_eq!(1, errors);
+
+    let mut pipe = Ditto::<Option<u8>>::default()
+        .ok_or(ReadError)
+        .connect(Lazy::new(|item: Result<u8, ReadError>| item.map(Some)))
+        .transpose_result();
+    assert_eq!(Some(Ok(4)), pipe.next(Some(4)));
+    assert_eq!(Some(Err(ReadError)), pipe.next(None));
+
+    let mut pipe = Ditto::<Result<u8, ReadError>>::default().ok();
+    assert_eq!(Some(4), pipe.next(Ok(4)));
+    assert_eq!(None, pipe.next(Err(ReadError)));
+}
+
+#[test]
+fn try_connect_short_circuit() {
+    use crate::{Ditto, Lazy};
+
+    let mut pipe = Ditto::<Result<u8, &str>>::default()
+        .try_connect(Ditto::default().enumerate())
+        .try_connect(Lazy::new(|(index, item): (usize, u8)| {
+            if item < 128 {
+                Ok((index, item * 2))
+            } else {
+                Err("overflow")
+            }
+        }));
+
+    assert_eq!(Ok(Ok((0, 2))), pipe.next(Ok(1)));
+    assert_eq!(Err("first"), pipe.next(Err("first")));
+    assert_eq!(Err("second"), pipe.next(Err("second")));
+    assert_eq!(Ok(Err("overflow")), pipe.next(Ok(200)));
+    // The enumeration only advanced for the `Ok` items.
+    assert_eq!(Ok(Ok((2, 4))), pipe.next(Ok(2)));
+}
+
+#[test]
+fn try_compose() {
+    use crate::{Ditto, Lazy};
+
+    let mut pipe = Ditto::<Result<u8, &str>>::default().try_compose()
+        >> Ditto::default().enumerate()
+        >> Lazy::new(|(index, item): (usize, u8)| index as u8 + item);
+
+    assert_eq!(Ok(4), pipe.next(Ok(4)));
+    assert_eq!(Err("error"), pipe.next(Err("error")));
+    assert_eq!(Ok(5), pipe.next(Ok(4)));
+
+    pipe.reset();
+    assert_eq!(Ok(4), pipe.next(Ok(4)));
+}
+
+#[test]
+fn unified_errors() {
+    use crate::{Ditto, Lazy};
+    use std::num::ParseIntError;
+
+    #[derive(Debug, PartialEq)]
+    enum Error {
+        Io(String),
+        Parse(ParseIntError),
+    }
+
+    impl From<ParseIntError> for Error {
+        fn from(error: ParseIntError) -> Self {
+            Error::Parse(error)
+        }
+    }
+
+    let mut pipe = Ditto::<Result<&str, std::io::ErrorKind>>::default()
+        .map_err(|kind| Error::Io(format!("{:?}", kind)))
+        .try_connect(Lazy::new(|line: &str| line.parse::<u8>()).err_into::<Error>())
+        .connect(Lazy::new(|item: Result<Result<u8, Error>, Error>| {
+            item.and_then(|item| item)
+        }));
+
+    assert_eq!(Ok(42), pipe.next(Ok("42")));
+    assert_eq!(
+        Err(Error::Io("UnexpectedEof".to_string())),
+        pipe.next(Err(std::io::ErrorKind::UnexpectedEof))
+    );
+    assert_eq!(
+        Err(Error::Parse("foo".parse::<u8>().unwrap_err())),
+        pipe.next(Ok("foo"))
+    );
+}
+
+#[test]
+fn map_err_stateful() {
+    use crate::Lazy;
+
+    let mut errors = 0;
+    let mut pipe = Lazy::new(|i: &str| i.parse::<u8>()).map_err(move |_| {
+        errors += 1;
+        errors
+    });
+    assert_eq!(Err(1), pipe.next("foo"));
+    assert_eq!(Ok(42), pipe.next("42"));
+    assert_eq!(Err(2), pipe.next("bar"));
+}
+
+#[test]
+fn retry() {
+    use crate::testing::MockPipe;
+    use crate::Ditto;
+
+    let mut attempts: Vec<(usize, usize)> = Vec::new();
+    let mut mock: MockPipe<u8, Result<u8, usize>> = MockPipe::new(vec![
+        Ok(1),
+        Err(2),
+        Err(3),
+        Ok(2),
+        Err(5),
+        Err(6),
+        Err(7),
+        Err(8),
+    ]);
+    let mut pipe = (&mut mock)
+        .retry_with(3, |attempt, error: &usize| attempts.push((attempt, *error)))
+        .connect(Ditto::default().enumerate());
+
+    assert_eq!((0, Ok(1)), pipe.next(1));
+    assert_eq!((1, Ok(2)), pipe.next(2));
+    assert_eq!((2, Err(8)), pipe.next(3));
+
+    assert_eq!(vec![(1, 2), (2, 3), (1, 5), (2, 6), (3, 7)], attempts);
+    assert_eq!(&[1, 2, 2, 2, 3, 3, 3, 3], mock.inputs());
+}
+
+#[test]
+fn try_convert() {
+    use crate::{Counter, Lazy};
+
+    let mut pipe = Lazy::new(|i: i64| i * 2).try_convert::<i16>();
+    assert_eq!(Ok(-32768), pipe.next(-16384));
+    assert_eq!(Ok(32766), pipe.next(16383));
+    assert!(pipe.next(16384).is_err());
+    assert!(pipe.next(-16385).is_err());
+
+    // Out-of-range samples are replaced by the downstream error handling.
+    let mut pipe = Counter::new(32766i64, 1)
+        .try_convert::<i16>()
+        .try_connect(Lazy::new(|i: i16| i / 2))
+        .map_err(|_| "out of range")
+        .compose()
+        >> Lazy::new(|i: Result<i16, &str>| {
+            i.unwrap_or_else(|error| {
+                assert_eq!("out of range", error);
+                0
+            })
+        });
+    assert_eq!(16383, pipe.next(()));
+    assert_eq!(16383, pipe.next(()));
+    assert_eq!(0, pipe.next(()));
+    pipe.reset();
+    assert_eq!(16383, pipe.next(()));
+}
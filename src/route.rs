@@ -0,0 +1,328 @@
+use crate::{ConsumeResult, Pipe, ResetablePipe};
+
+/// The consumers of a distributor and the policy for full consumers.
+struct Consumers<P: Pipe> {
+    consumers: Vec<P>,
+    skip_full: Option<SkipFull<P>>,
+}
+
+/// The functions needed to offer an item to the next consumer if a consumer is full.
+struct SkipFull<P: Pipe> {
+    clone: fn(&P::InputItem) -> P::InputItem,
+    is_full: fn(&P::OutputItem) -> bool,
+}
+
+impl<P: Pipe> Consumers<P> {
+    fn new(consumers: Vec<P>) -> Self {
+        assert!(
+            !consumers.is_empty(),
+            "A distributor needs at least one consumer"
+        );
+        Self {
+            consumers,
+            skip_full: None,
+        }
+    }
+
+    /// Offer the item to the consumers, starting with the consumer at `start`.
+    fn send(&mut self, start: usize, item: P::InputItem) -> (usize, P::OutputItem) {
+        let skip_full = match &self.skip_full {
+            Some(skip_full) => skip_full,
+            None => return (start, self.consumers[start].next(item)),
+        };
+
+        let len = self.consumers.len();
+        for offset in 0..len - 1 {
+            let index = (start + offset) % len;
+            let output = self.consumers[index].next((skip_full.clone)(&item));
+            if !(skip_full.is_full)(&output) {
+                return (index, output);
+            }
+        }
+        let index = (start + len - 1) % len;
+        (index, self.consumers[index].next(item))
+    }
+
+    fn finish(&mut self) -> Vec<(usize, P::OutputItem)> {
+        self.consumers
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(index, consumer)| {
+                consumer
+                    .finish()
+                    .into_iter()
+                    .map(move |output| (index, output))
+            })
+            .collect()
+    }
+}
+
+impl<P> Consumers<P>
+where
+    P: Pipe<OutputItem = ConsumeResult>,
+    P::InputItem: Clone,
+{
+    fn skip_full(&mut self) {
+        self.skip_full = Some(SkipFull {
+            clone: P::InputItem::clone,
+            is_full: |result| *result == ConsumeResult::Full,
+        });
+    }
+}
+
+impl<P: ResetablePipe> Consumers<P> {
+    fn reset(&mut self) {
+        for consumer in self.consumers.iter_mut() {
+            consumer.reset();
+        }
+    }
+}
+
+/// A pipe that distributes its input items across several consumer pipes in turn.
+///
+/// Every call to `next` forwards the input item to the current consumer, advances the cursor to the next consumer and returns the index of the consumer along with its output item. This is useful to write alternating samples into per-channel buffers or to balance work across several consumers. If the consumers should be chosen by a key of the input item instead, use [`distribute_by`](#method.distribute_by).
+///
+/// If the consumers return a [`ConsumeResult`](enum.ConsumeResult.html), full consumers can be skipped using [`skip_full`](#method.skip_full). Then, an item that was rejected by a consumer is offered to the following consumers until one of them consumes it. If all consumers are full, the item is dropped and the index of the last consumer that was tried is returned along with `ConsumeResult::Full`. In any case, the cursor advances to the consumer following the returned one.
+///
+/// Resetting this pipe resets all consumers and moves the cursor back to the first consumer.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipe = RoundRobin::new(vec![ScaleOffset::new(2u8, 0), ScaleOffset::new(3u8, 0)]);
+/// assert_eq!((0, 2), pipe.next(1));
+/// assert_eq!((1, 3), pipe.next(1));
+/// assert_eq!((0, 2), pipe.next(1));
+/// ```
+pub struct RoundRobin<P: Pipe> {
+    consumers: Consumers<P>,
+    cursor: usize,
+}
+
+impl<P: Pipe> RoundRobin<P> {
+    /// Create a new round-robin distributor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `consumers` is empty.
+    pub fn new(consumers: Vec<P>) -> Self {
+        Self {
+            consumers: Consumers::new(consumers),
+            cursor: 0,
+        }
+    }
+
+    /// Choose the consumer of every input item by a key function instead of rotation.
+    ///
+    /// The key function returns the index of the consumer for an input item, which is reduced modulo the number of consumers. If full consumers are skipped, a rejected item is offered to the consumers following the chosen one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = RoundRobin::new(vec![Ditto::default(), Ditto::default()])
+    ///     .distribute_by(|i: &u8| *i as usize);
+    /// assert_eq!((1, 3), pipe.next(3));
+    /// assert_eq!((0, 4), pipe.next(4));
+    /// ```
+    pub fn distribute_by<F>(self, key: F) -> DistributeBy<P, F>
+    where
+        F: FnMut(&P::InputItem) -> usize,
+    {
+        DistributeBy {
+            consumers: self.consumers,
+            key,
+        }
+    }
+
+    /// Return the consumers.
+    pub fn consumers(&self) -> &[P] {
+        &self.consumers.consumers
+    }
+
+    /// Return the consumers mutably.
+    pub fn consumers_mut(&mut self) -> &mut [P] {
+        &mut self.consumers.consumers
+    }
+}
+
+impl<P> RoundRobin<P>
+where
+    P: Pipe<OutputItem = ConsumeResult>,
+    P::InputItem: Clone,
+{
+    /// Skip consumers that report `ConsumeResult::Full`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let mut pipe = RoundRobin::new(vec![RingConsumer::new(4), RingConsumer::new(4)]).skip_full();
+    /// assert_eq!((0, ConsumeResult::Consumed), pipe.next(1.0));
+    /// assert_eq!((1, ConsumeResult::Consumed), pipe.next(2.0));
+    /// ```
+    pub fn skip_full(mut self) -> Self {
+        self.consumers.skip_full();
+        self
+    }
+}
+
+impl<P: Pipe> Pipe for RoundRobin<P> {
+    type InputItem = P::InputItem;
+    type OutputItem = (usize, P::OutputItem);
+
+    fn next(&mut self, item: P::InputItem) -> (usize, P::OutputItem) {
+        let (index, output) = self.consumers.send(self.cursor, item);
+        self.cursor = (index + 1) % self.consumers.consumers.len();
+        (index, output)
+    }
+
+    fn finish(&mut self) -> Vec<(usize, P::OutputItem)> {
+        self.consumers.finish()
+    }
+}
+
+impl<P: ResetablePipe> ResetablePipe for RoundRobin<P> {
+    fn reset(&mut self) {
+        self.consumers.reset();
+        self.cursor = 0;
+    }
+}
+
+/// A pipe that distributes its input items across several consumer pipes by a key function.
+///
+/// For more information, please see [the documentation of the `distribute_by` method](struct.RoundRobin.html#method.distribute_by).
+pub struct DistributeBy<P, F>
+where
+    P: Pipe,
+    F: FnMut(&P::InputItem) -> usize,
+{
+    consumers: Consumers<P>,
+    key: F,
+}
+
+impl<P, F> DistributeBy<P, F>
+where
+    P: Pipe,
+    F: FnMut(&P::InputItem) -> usize,
+{
+    /// Return the consumers.
+    pub fn consumers(&self) -> &[P] {
+        &self.consumers.consumers
+    }
+
+    /// Return the consumers mutably.
+    pub fn consumers_mut(&mut self) -> &mut [P] {
+        &mut self.consumers.consumers
+    }
+}
+
+impl<P, F> DistributeBy<P, F>
+where
+    P: Pipe<OutputItem = ConsumeResult>,
+    P::InputItem: Clone,
+    F: FnMut(&P::InputItem) -> usize,
+{
+    /// Skip consumers that report `ConsumeResult::Full`.
+    pub fn skip_full(mut self) -> Self {
+        self.consumers.skip_full();
+        self
+    }
+}
+
+impl<P, F> Pipe for DistributeBy<P, F>
+where
+    P: Pipe,
+    F: FnMut(&P::InputItem) -> usize,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = (usize, P::OutputItem);
+
+    fn next(&mut self, item: P::InputItem) -> (usize, P::OutputItem) {
+        let start = (self.key)(&item) % self.consumers.consumers.len();
+        self.consumers.send(start, item)
+    }
+
+    fn finish(&mut self) -> Vec<(usize, P::OutputItem)> {
+        self.consumers.finish()
+    }
+}
+
+impl<P, F> ResetablePipe for DistributeBy<P, F>
+where
+    P: ResetablePipe,
+    F: FnMut(&P::InputItem) -> usize,
+{
+    fn reset(&mut self) {
+        self.consumers.reset();
+    }
+}
+
+#[test]
+fn round_robin_rotation() {
+    use crate::testing::MockPipe;
+
+    let consumers: Vec<MockPipe<u8, ()>> = (0..3).map(|_| MockPipe::cycling(vec![()])).collect();
+    let mut pipe = RoundRobin::new(consumers);
+    let indices: Vec<usize> = (0..7).map(|i| pipe.next(i).0).collect();
+    assert_eq!(vec![0, 1, 2, 0, 1, 2, 0], indices);
+    assert_eq!(&[0, 3, 6], pipe.consumers()[0].inputs());
+    assert_eq!(&[1, 4], pipe.consumers()[1].inputs());
+    assert_eq!(&[2, 5], pipe.consumers()[2].inputs());
+
+    pipe.reset();
+    assert_eq!((0, ()), pipe.next(7));
+    assert!(pipe
+        .consumers()
+        .iter()
+        .all(|consumer| consumer.resets() == 1));
+}
+
+#[test]
+fn round_robin_keyed() {
+    use crate::testing::MockPipe;
+
+    let consumers: Vec<MockPipe<u8, ()>> = (0..3).map(|_| MockPipe::cycling(vec![()])).collect();
+    let mut pipe = RoundRobin::new(consumers).distribute_by(|i: &u8| *i as usize);
+    for i in 0..10 {
+        assert_eq!(i as usize % 3, pipe.next(i).0);
+    }
+    assert_eq!(&[0, 3, 6, 9], pipe.consumers()[0].inputs());
+    assert_eq!(&[1, 4, 7], pipe.consumers()[1].inputs());
+    assert_eq!(&[2, 5, 8], pipe.consumers()[2].inputs());
+}
+
+#[test]
+fn round_robin_skip_full() {
+    use crate::testing::MockPipe;
+    use ConsumeResult::*;
+
+    let consumers: Vec<MockPipe<u8, ConsumeResult>> = vec![
+        MockPipe::cycling(vec![Full]),
+        MockPipe::cycling(vec![Consumed]),
+        MockPipe::cycling(vec![Consumed, Full]),
+    ];
+    let mut pipe = RoundRobin::new(consumers).skip_full();
+    assert_eq!((1, Consumed), pipe.next(0));
+    assert_eq!((2, Consumed), pipe.next(1));
+    assert_eq!((1, Consumed), pipe.next(2));
+    assert_eq!(&[0, 2], pipe.consumers()[0].inputs());
+    assert_eq!(&[0, 2], pipe.consumers()[1].inputs());
+    assert_eq!(&[1], pipe.consumers()[2].inputs());
+
+    // If all consumers are full, the item is dropped.
+    let consumers: Vec<MockPipe<u8, ConsumeResult>> =
+        (0..3).map(|_| MockPipe::cycling(vec![Full])).collect();
+    let mut pipe = RoundRobin::new(consumers)
+        .distribute_by(|i: &u8| *i as usize)
+        .skip_full();
+    assert_eq!((0, Full), pipe.next(1));
+    assert!(pipe
+        .consumers()
+        .iter()
+        .all(|consumer| consumer.calls() == 1));
+}
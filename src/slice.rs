@@ -0,0 +1,532 @@
+use crate::{Pipe, ResetablePipe};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
+/// A pipe that yields references to the elements of a slice.
+///
+/// Every call to `next` returns a reference to the next element of the slice, or `None` if the slice is exhausted. In contrast to wrapping the slice's iterator in a [`PipeIter`](struct.PipeIter.html), this producer remembers the slice and can therefore be reseted to start from the beginning again.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// const DATA: &[u8] = &[3, 2, 1];
+///
+/// let mut producer = SliceProducer::new(DATA);
+/// assert_eq!(Some(&3), producer.next(()));
+/// assert_eq!(Some(&2), producer.next(()));
+/// producer.reset();
+/// assert_eq!(Some(&3), producer.next(()));
+/// ```
+pub struct SliceProducer<'a, T> {
+    data: &'a [T],
+    index: usize,
+}
+
+impl<'a, T> SliceProducer<'a, T> {
+    /// Create a new slice producer.
+    pub fn new(data: &'a [T]) -> Self {
+        Self { data, index: 0 }
+    }
+}
+
+impl<'a, T> Pipe for SliceProducer<'a, T> {
+    type InputItem = ();
+    type OutputItem = Option<&'a T>;
+
+    fn next(&mut self, _: ()) -> Option<&'a T> {
+        let item = self.data.get(self.index);
+        if item.is_some() {
+            self.index += 1;
+        }
+        item
+    }
+}
+
+impl<'a, T> ResetablePipe for SliceProducer<'a, T> {
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+}
+
+/// A pipe that yields mutable references to the elements of a slice.
+///
+/// Every call to `next` returns a mutable reference to the next element of the slice, or `None` if the slice is exhausted.
+///
+/// Since handing out a mutable reference to the same element twice would alias it, this producer can not be reseted and therefore doesn't implement `ResetablePipe`. If you need a resetable producer, use the immutable [`SliceProducer`](struct.SliceProducer.html) instead.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut data: [u8; 3] = [3, 2, 1];
+///
+/// let mut producer = SliceProducerMut::new(&mut data);
+/// while let Some(item) = producer.next(()) {
+///     *item *= 2;
+/// }
+///
+/// assert_eq!([6, 4, 2], data);
+/// ```
+pub struct SliceProducerMut<'a, T> {
+    iter: std::slice::IterMut<'a, T>,
+}
+
+impl<'a, T> SliceProducerMut<'a, T> {
+    /// Create a new mutable slice producer.
+    pub fn new(data: &'a mut [T]) -> Self {
+        Self {
+            iter: data.iter_mut(),
+        }
+    }
+}
+
+impl<'a, T> Pipe for SliceProducerMut<'a, T> {
+    type InputItem = ();
+    type OutputItem = Option<&'a mut T>;
+
+    fn next(&mut self, _: ()) -> Option<&'a mut T> {
+        self.iter.next()
+    }
+}
+
+/// A pipe that cycles through a collection of items forever.
+///
+/// Every call to `next` returns a clone of the next item and once the end of the collection is reached, it wraps around and starts from the beginning again. This is useful for wavetables or test vectors that should be looped endlessly. The items can either be owned, if the pipe is created with [`new`](#method.new), or borrowed, if it's created with [`from_slice`](#method.from_slice).
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipe = CyclePipe::new(vec![1, 2, 3]);
+/// assert_eq!(1, pipe.next(()));
+/// assert_eq!(2, pipe.next(()));
+/// assert_eq!(3, pipe.next(()));
+/// assert_eq!(1, pipe.next(()));
+/// ```
+pub struct CyclePipe<'a, T: Clone> {
+    data: Cow<'a, [T]>,
+    index: usize,
+}
+
+impl<T: Clone> CyclePipe<'static, T> {
+    /// Create a new cycling pipe that owns its items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector is empty.
+    pub fn new(data: Vec<T>) -> Self {
+        assert!(
+            !data.is_empty(),
+            "Can not cycle through an empty collection"
+        );
+        Self {
+            data: Cow::Owned(data),
+            index: 0,
+        }
+    }
+}
+
+impl<'a, T: Clone> CyclePipe<'a, T> {
+    /// Create a new cycling pipe that borrows its items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice is empty.
+    pub fn from_slice(data: &'a [T]) -> Self {
+        assert!(
+            !data.is_empty(),
+            "Can not cycle through an empty collection"
+        );
+        Self {
+            data: Cow::Borrowed(data),
+            index: 0,
+        }
+    }
+}
+
+impl<'a, T: Clone> Pipe for CyclePipe<'a, T> {
+    type InputItem = ();
+    type OutputItem = T;
+
+    fn next(&mut self, _: ()) -> T {
+        let item = self.data[self.index].clone();
+        self.index = (self.index + 1) % self.data.len();
+        item
+    }
+}
+
+impl<'a, T: Clone> ResetablePipe for CyclePipe<'a, T> {
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+}
+
+/// A pipe that yields non-overlapping chunks of a slice.
+///
+/// Every call to `next` returns the next `chunk_len` elements of the slice. If the length of the slice isn't a multiple of the chunk length, the last chunk is shorter, just like with [`chunks`](https://doc.rust-lang.org/std/primitive.slice.html#method.chunks). If the producer was created with `drop_remainder` set to `true`, this shorter chunk is omitted, just like with [`chunks_exact`](https://doc.rust-lang.org/std/primitive.slice.html#method.chunks_exact). Once the slice is exhausted, `None` is returned.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// const DATA: &[u8] = &[0, 1, 2, 3, 4];
+///
+/// let mut producer = ChunkProducer::new(DATA, 2, false);
+/// assert_eq!(Some(&[0, 1][..]), producer.next(()));
+/// assert_eq!(Some(&[2, 3][..]), producer.next(()));
+/// assert_eq!(Some(&[4][..]), producer.next(()));
+/// assert_eq!(None, producer.next(()));
+///
+/// let mut producer = ChunkProducer::new(DATA, 2, true);
+/// assert_eq!(Some(&[0, 1][..]), producer.next(()));
+/// assert_eq!(Some(&[2, 3][..]), producer.next(()));
+/// assert_eq!(None, producer.next(()));
+/// ```
+pub struct ChunkProducer<'a, T> {
+    data: &'a [T],
+    chunk_len: usize,
+    drop_remainder: bool,
+    index: usize,
+}
+
+impl<'a, T> ChunkProducer<'a, T> {
+    /// Create a new chunk producer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_len` is zero.
+    pub fn new(data: &'a [T], chunk_len: usize, drop_remainder: bool) -> Self {
+        assert!(chunk_len != 0, "The chunk length must not be zero");
+        Self {
+            data,
+            chunk_len,
+            drop_remainder,
+            index: 0,
+        }
+    }
+}
+
+impl<'a, T> Pipe for ChunkProducer<'a, T> {
+    type InputItem = ();
+    type OutputItem = Option<&'a [T]>;
+
+    fn next(&mut self, _: ()) -> Option<&'a [T]> {
+        let remaining = self.data.len() - self.index;
+        if remaining == 0 || (self.drop_remainder && remaining < self.chunk_len) {
+            return None;
+        }
+        let end = self.index + usize::min(remaining, self.chunk_len);
+        let chunk = &self.data[self.index..end];
+        self.index = end;
+        Some(chunk)
+    }
+}
+
+impl<'a, T> ResetablePipe for ChunkProducer<'a, T> {
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+}
+
+/// The result of a consuming pipe.
+///
+/// Consumers write their input items somewhere and report whether the item could be stored or whether the consumer is already full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsumeResult {
+    /// The item was consumed.
+    Consumed,
+    /// The consumer is full and the item was dropped.
+    Full,
+}
+
+/// A pipe that writes its input items into a slice.
+///
+/// Every input item is written to the next element of the slice. Once the slice is full, the items are dropped and `ConsumeResult::Full` is returned. This is the counterpart of the [`SliceProducer`](struct.SliceProducer.html) and, like every pipe that returns a `ConsumeResult`, it's a [`Consumer`](trait.Consumer.html).
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut data: [u8; 2] = [0; 2];
+///
+/// let mut consumer = SliceConsumer::new(&mut data);
+/// assert_eq!(ConsumeResult::Consumed, consumer.next(1));
+/// assert_eq!(ConsumeResult::Consumed, consumer.consume(2));
+/// assert_eq!(ConsumeResult::Full, consumer.next(3));
+/// assert_eq!(2, consumer.written());
+///
+/// assert_eq!([1, 2], data);
+/// ```
+pub struct SliceConsumer<'a, T> {
+    data: &'a mut [T],
+    index: usize,
+}
+
+impl<'a, T> SliceConsumer<'a, T> {
+    /// Create a new slice consumer.
+    pub fn new(data: &'a mut [T]) -> Self {
+        Self { data, index: 0 }
+    }
+
+    /// Return the number of items written so far.
+    pub fn written(&self) -> usize {
+        self.index
+    }
+}
+
+impl<'a, T> Pipe for SliceConsumer<'a, T> {
+    type InputItem = T;
+    type OutputItem = ConsumeResult;
+
+    fn next(&mut self, item: T) -> ConsumeResult {
+        match self.data.get_mut(self.index) {
+            Some(slot) => {
+                *slot = item;
+                self.index += 1;
+                ConsumeResult::Consumed
+            }
+            None => ConsumeResult::Full,
+        }
+    }
+}
+
+impl<'a, T> ResetablePipe for SliceConsumer<'a, T> {
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+}
+
+/// A pipe that reads interleaved frames from a flat slice.
+///
+/// Audio buffers are often interleaved, which means that the samples of the different channels alternate. This producer reads `CH` consecutive elements per call and returns them as one frame. Once the slice is exhausted, `None` is returned.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// const DATA: &[f32] = &[0.0, 1.0, 2.0, 3.0];
+///
+/// let mut producer: FrameProducer<f32, 2> = FrameProducer::new(DATA);
+/// assert_eq!(Some([0.0, 1.0]), producer.next(()));
+/// assert_eq!(Some([2.0, 3.0]), producer.next(()));
+/// assert_eq!(None, producer.next(()));
+/// ```
+pub struct FrameProducer<'a, T: Copy, const CH: usize> {
+    data: &'a [T],
+    index: usize,
+}
+
+impl<'a, T: Copy, const CH: usize> FrameProducer<'a, T, CH> {
+    /// Create a new frame producer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `CH` is zero or if the length of the slice isn't a multiple of `CH`.
+    pub fn new(data: &'a [T]) -> Self {
+        assert!(CH != 0, "The number of channels must not be zero");
+        assert!(
+            data.len().is_multiple_of(CH),
+            "The length of the slice must be a multiple of the number of channels"
+        );
+        Self { data, index: 0 }
+    }
+}
+
+impl<'a, T: Copy, const CH: usize> Pipe for FrameProducer<'a, T, CH> {
+    type InputItem = ();
+    type OutputItem = Option<[T; CH]>;
+
+    fn next(&mut self, _: ()) -> Option<[T; CH]> {
+        let frame = self.data.get(self.index..self.index + CH)?;
+        self.index += CH;
+        // The length of the slice is checked above, so this conversion can not fail.
+        Some(<[T; CH]>::try_from(frame).unwrap())
+    }
+}
+
+impl<'a, T: Copy, const CH: usize> ResetablePipe for FrameProducer<'a, T, CH> {
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+}
+
+/// A pipe that writes interleaved frames into a flat slice.
+///
+/// This is the counterpart of the [`FrameProducer`](struct.FrameProducer.html): Every input frame is written to the next `CH` elements of the slice. Once the slice is full, the frames are dropped and `ConsumeResult::Full` is returned.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut data: [f32; 4] = [0.0; 4];
+///
+/// let mut consumer: FrameConsumer<f32, 2> = FrameConsumer::new(&mut data);
+/// assert_eq!(ConsumeResult::Consumed, consumer.next([0.0, 1.0]));
+/// assert_eq!(ConsumeResult::Consumed, consumer.next([2.0, 3.0]));
+/// assert_eq!(ConsumeResult::Full, consumer.next([4.0, 5.0]));
+///
+/// assert_eq!([0.0, 1.0, 2.0, 3.0], data);
+/// ```
+pub struct FrameConsumer<'a, T: Copy, const CH: usize> {
+    data: &'a mut [T],
+    index: usize,
+}
+
+impl<'a, T: Copy, const CH: usize> FrameConsumer<'a, T, CH> {
+    /// Create a new frame consumer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `CH` is zero or if the length of the slice isn't a multiple of `CH`.
+    pub fn new(data: &'a mut [T]) -> Self {
+        assert!(CH != 0, "The number of channels must not be zero");
+        assert!(
+            data.len().is_multiple_of(CH),
+            "The length of the slice must be a multiple of the number of channels"
+        );
+        Self { data, index: 0 }
+    }
+}
+
+impl<'a, T: Copy, const CH: usize> Pipe for FrameConsumer<'a, T, CH> {
+    type InputItem = [T; CH];
+    type OutputItem = ConsumeResult;
+
+    fn next(&mut self, frame: [T; CH]) -> ConsumeResult {
+        match self.data.get_mut(self.index..self.index + CH) {
+            Some(slot) => {
+                slot.copy_from_slice(&frame);
+                self.index += CH;
+                ConsumeResult::Consumed
+            }
+            None => ConsumeResult::Full,
+        }
+    }
+}
+
+impl<'a, T: Copy, const CH: usize> ResetablePipe for FrameConsumer<'a, T, CH> {
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+}
+
+#[test]
+fn slice_producer_reset() {
+    use crate::Lazy;
+
+    let data: Vec<u32> = (0..16).collect();
+    let mut pipeline =
+        SliceProducer::new(&data).compose() >> Lazy::new(|i: Option<&u32>| i.map(|i| i * 2));
+
+    let mut first_run: Vec<u32> = Vec::new();
+    while let Some(item) = pipeline.next(()) {
+        first_run.push(item);
+    }
+
+    pipeline.reset();
+
+    let mut second_run: Vec<u32> = Vec::new();
+    while let Some(item) = pipeline.next(()) {
+        second_run.push(item);
+    }
+
+    assert_eq!(first_run.len(), 16);
+    assert_eq!(first_run, second_run);
+}
+
+#[test]
+fn cycle_pipe() {
+    let mut pipe = CyclePipe::new(vec![0, 1, 2]);
+    let items: Vec<usize> = (0..7).map(|_| pipe.next(())).collect();
+    assert_eq!(vec![0, 1, 2, 0, 1, 2, 0], items);
+
+    pipe.reset();
+    assert_eq!(0, pipe.next(()));
+
+    let data = [3, 4];
+    let mut pipe = CyclePipe::from_slice(&data);
+    assert_eq!(3, pipe.next(()));
+    pipe.reset();
+    assert_eq!(3, pipe.next(()));
+    assert_eq!(4, pipe.next(()));
+    assert_eq!(3, pipe.next(()));
+}
+
+#[test]
+#[should_panic(expected = "Can not cycle through an empty collection")]
+fn cycle_pipe_empty() {
+    CyclePipe::<u8>::new(Vec::new());
+}
+
+#[test]
+fn chunk_producer_multiple() {
+    let data: Vec<usize> = (0..6).collect();
+    let chunks: Vec<&[usize]> = ChunkProducer::new(&data, 3, false).into_iter().collect();
+    assert_eq!(chunks, vec![&[0, 1, 2][..], &[3, 4, 5][..]]);
+
+    let chunks: Vec<&[usize]> = ChunkProducer::new(&data, 3, true).into_iter().collect();
+    assert_eq!(chunks, vec![&[0, 1, 2][..], &[3, 4, 5][..]]);
+}
+
+#[test]
+fn chunk_producer_remainder() {
+    let data: Vec<usize> = (0..7).collect();
+    let chunks: Vec<&[usize]> = ChunkProducer::new(&data, 3, false).into_iter().collect();
+    assert_eq!(chunks, vec![&[0, 1, 2][..], &[3, 4, 5][..], &[6][..]]);
+
+    let chunks: Vec<&[usize]> = ChunkProducer::new(&data, 3, true).into_iter().collect();
+    assert_eq!(chunks, vec![&[0, 1, 2][..], &[3, 4, 5][..]]);
+
+    let chunks: Vec<&[usize]> = ChunkProducer::new(&data[..2], 3, true)
+        .into_iter()
+        .collect();
+    assert!(chunks.is_empty());
+}
+
+#[test]
+fn chunk_producer_reset() {
+    let data: Vec<usize> = (0..5).collect();
+    let mut producer = ChunkProducer::new(&data, 2, false);
+    assert_eq!(Some(&[0, 1][..]), producer.next(()));
+    assert_eq!(Some(&[2, 3][..]), producer.next(()));
+    producer.reset();
+    assert_eq!(Some(&[0, 1][..]), producer.next(()));
+    assert_eq!(Some(&[2, 3][..]), producer.next(()));
+    assert_eq!(Some(&[4][..]), producer.next(()));
+    assert_eq!(None, producer.next(()));
+}
+
+#[test]
+fn frame_round_trip() {
+    use crate::Lazy;
+
+    let input: Vec<f32> = (0..16).map(|i| i as f32).collect();
+    let mut output: Vec<f32> = vec![0.0; 16];
+
+    let mut pipeline = FrameProducer::<f32, 2>::new(&input).compose()
+        >> Lazy::new(|frame: Option<[f32; 2]>| frame.map(|[l, r]| [r, l * 2.0]))
+        >> FrameConsumer::<f32, 2>::new(&mut output).optional();
+
+    while let Some(result) = pipeline.next(()) {
+        assert_eq!(ConsumeResult::Consumed, result);
+    }
+
+    for (input, output) in input.chunks(2).zip(output.chunks(2)) {
+        assert_eq!(input[1], output[0]);
+        assert_eq!(input[0] * 2.0, output[1]);
+    }
+}
+
+#[test]
+#[should_panic]
+fn frame_producer_uneven() {
+    FrameProducer::<f32, 2>::new(&[0.0; 3]);
+}
@@ -0,0 +1,333 @@
+//! Helpers for testing pipes and the glue between them.
+//!
+//! The [`MockPipe`](struct.MockPipe.html) returns scripted output items and records every input item it receives, which makes it easy to verify how decorators and drivers invoke the pipes they wrap.
+//!
+//! The assertion helpers [`assert_pipes_equal`](fn.assert_pipes_equal.html), [`assert_pipe_matches`](fn.assert_pipe_matches.html) and [`assert_reset_law`](fn.assert_reset_law.html) compare the behavior of pipes over a sequence of input items.
+
+use crate::{LazyMut, Pipe, ResetablePipe};
+use std::fmt::Debug;
+
+/// A pipe that returns scripted output items and records its input items.
+///
+/// The mock is created from a vector of output items, which are returned in order. If `next` is called more often than there are scripted output items, the mock either panics or starts from the beginning again, depending on how it was created. Every input item is recorded and can be inspected with [`inputs`](#method.inputs).
+///
+/// Resetting the mock restarts the script from the beginning and increments the reset counter. The recorded input items are kept.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+/// use iterpipes::testing::MockPipe;
+///
+/// let mut mock: MockPipe<u8, u8> = MockPipe::new(vec![2, 4]);
+/// let mut pipe = (&mut mock).optional();
+///
+/// assert_eq!(Some(2), pipe.next(Some(1)));
+/// assert_eq!(None, pipe.next(None));
+/// assert_eq!(Some(4), pipe.next(Some(2)));
+///
+/// // `Optional` never forwards `None` inputs.
+/// assert_eq!(&[1, 2], mock.inputs());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockPipe<I, O: Clone> {
+    outputs: Vec<O>,
+    cycle: bool,
+    index: usize,
+    inputs: Vec<I>,
+    resets: usize,
+}
+
+impl<I, O: Clone> MockPipe<I, O> {
+    /// Create a new mock that panics once the scripted output items are exhausted.
+    pub fn new(outputs: Vec<O>) -> Self {
+        Self {
+            outputs,
+            cycle: false,
+            index: 0,
+            inputs: Vec::new(),
+            resets: 0,
+        }
+    }
+
+    /// Create a new mock that cycles through the scripted output items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `outputs` is empty.
+    pub fn cycling(outputs: Vec<O>) -> Self {
+        assert!(
+            !outputs.is_empty(),
+            "A cycling mock pipe needs at least one output item"
+        );
+        Self {
+            outputs,
+            cycle: true,
+            index: 0,
+            inputs: Vec::new(),
+            resets: 0,
+        }
+    }
+
+    /// Return all input items received so far.
+    pub fn inputs(&self) -> &[I] {
+        &self.inputs
+    }
+
+    /// Return the number of calls to `next` so far.
+    pub fn calls(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Return the number of calls to `reset` so far.
+    pub fn resets(&self) -> usize {
+        self.resets
+    }
+}
+
+impl<I, O: Clone> Pipe for MockPipe<I, O> {
+    type InputItem = I;
+    type OutputItem = O;
+
+    fn next(&mut self, input: I) -> O {
+        if self.index >= self.outputs.len() {
+            if self.cycle {
+                self.index = 0;
+            } else {
+                panic!(
+                    "MockPipe::next was called {} times, but only {} output items were scripted",
+                    self.inputs.len() + 1,
+                    self.outputs.len()
+                );
+            }
+        }
+        self.inputs.push(input);
+        let output = self.outputs[self.index].clone();
+        self.index += 1;
+        output
+    }
+}
+
+impl<I, O: Clone> ResetablePipe for MockPipe<I, O> {
+    fn reset(&mut self) {
+        self.index = 0;
+        self.resets += 1;
+    }
+}
+
+/// Assert that two pipes produce the same output items for the given input items.
+///
+/// Both pipes receive a clone of every input item and their output items are compared. If they differ, this function panics with a message containing the index of the input item and both output items.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+/// use iterpipes::testing::assert_pipes_equal;
+///
+/// let reference = Lazy::new(|i: u32| i * 4);
+/// let optimized = Lazy::new(|i: u32| i << 2);
+///
+/// assert_pipes_equal(reference, optimized, 0..1024);
+/// ```
+pub fn assert_pipes_equal<A, B, I>(mut left: A, mut right: B, inputs: I)
+where
+    A: Pipe,
+    B: Pipe<InputItem = A::InputItem>,
+    A::InputItem: Clone,
+    A::OutputItem: PartialEq<B::OutputItem> + Debug,
+    B::OutputItem: Debug,
+    I: IntoIterator<Item = A::InputItem>,
+{
+    for (index, input) in inputs.into_iter().enumerate() {
+        let left_output = left.next(input.clone());
+        let right_output = right.next(input);
+        if left_output != right_output {
+            panic!(
+                "The pipes differ at input item {}: left = {:?}, right = {:?}",
+                index, left_output, right_output
+            );
+        }
+    }
+}
+
+/// Assert that a pipe produces the same output items as a reference function.
+///
+/// This is a shorthand for [`assert_pipes_equal`](fn.assert_pipes_equal.html) with the reference function wrapped in a [`LazyMut`](../struct.LazyMut.html).
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+/// use iterpipes::testing::assert_pipe_matches;
+///
+/// assert_pipe_matches(
+///     Ditto::<u32>::default().enumerate(),
+///     |i: u32| (i as usize, i),
+///     0..16,
+/// );
+/// ```
+pub fn assert_pipe_matches<P, F, I>(pipe: P, reference: F, inputs: I)
+where
+    P: Pipe,
+    P::InputItem: Clone,
+    P::OutputItem: PartialEq + Debug,
+    F: FnMut(P::InputItem) -> P::OutputItem,
+    I: IntoIterator<Item = P::InputItem>,
+{
+    assert_pipes_equal(pipe, LazyMut::new(reference), inputs);
+}
+
+/// Assert that resetting a pipe restores its initial behavior.
+///
+/// The pipe processes all input items, is reseted and processes them again. If any output item of the second run differs from the respective item of the first run, this function panics with a message containing the index of the input item and both output items.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+/// use iterpipes::testing::assert_reset_law;
+///
+/// assert_reset_law(Counter::new(0u32, 3), vec![(); 16]);
+/// ```
+pub fn assert_reset_law<P, I>(mut pipe: P, inputs: I)
+where
+    P: ResetablePipe,
+    P::InputItem: Clone,
+    P::OutputItem: PartialEq + Debug,
+    I: IntoIterator<Item = P::InputItem>,
+{
+    let inputs: Vec<P::InputItem> = inputs.into_iter().collect();
+    let first_run: Vec<P::OutputItem> = inputs.iter().cloned().map(|i| pipe.next(i)).collect();
+
+    pipe.reset();
+
+    for (index, (input, expected)) in inputs.into_iter().zip(first_run).enumerate() {
+        let output = pipe.next(input);
+        if output != expected {
+            panic!(
+                "The pipe behaves differently after a reset at input item {}: before = {:?}, after = {:?}",
+                index, expected, output
+            );
+        }
+    }
+}
+
+#[test]
+fn mock_pipe_script() {
+    let mut mock: MockPipe<char, u8> = MockPipe::new(vec![1, 2, 3]);
+    assert_eq!(1, mock.next('a'));
+    assert_eq!(2, mock.next('b'));
+    mock.reset();
+    assert_eq!(1, mock.next('c'));
+    assert_eq!(2, mock.next('d'));
+    assert_eq!(3, mock.next('e'));
+
+    assert_eq!(&['a', 'b', 'c', 'd', 'e'], mock.inputs());
+    assert_eq!(5, mock.calls());
+    assert_eq!(1, mock.resets());
+}
+
+#[test]
+#[should_panic(
+    expected = "MockPipe::next was called 3 times, but only 2 output items were scripted"
+)]
+fn mock_pipe_exhausted() {
+    let mut mock: MockPipe<(), u8> = MockPipe::new(vec![1, 2]);
+    for _ in 0..3 {
+        mock.next(());
+    }
+}
+
+#[test]
+fn mock_pipe_cycling() {
+    let mut mock: MockPipe<(), u8> = MockPipe::cycling(vec![1, 2]);
+    let outputs: Vec<u8> = (0..5).map(|_| mock.next(())).collect();
+    assert_eq!(vec![1, 2, 1, 2, 1], outputs);
+}
+
+#[test]
+fn reset_is_forwarded() {
+    let mut mock: MockPipe<u8, u8> = MockPipe::new(vec![]);
+    {
+        let mut pipe = (&mut mock).compose() >> crate::Ditto::default();
+        pipe.reset();
+        let mut pipe = (&mut mock).optional().enumerate().bypass();
+        pipe.reset();
+    }
+    assert_eq!(2, mock.resets());
+}
+
+#[test]
+fn decorator_reset_laws() {
+    use crate::{BoundedCounter, Counter, Ditto, SliceProducer};
+
+    assert_reset_law(Ditto::<u8>::default().enumerate(), 0..32);
+    assert_reset_law(
+        Counter::new(0u8, 1).compose() >> Ditto::default(),
+        vec![(); 32],
+    );
+    assert_reset_law(
+        BoundedCounter::new(0, 10, 3).unwrap_or(-1).enumerate(),
+        vec![(); 8],
+    );
+    assert_reset_law((Counter::new(0u8, 2), Ditto::default()), vec![((), 'a'); 8]);
+
+    let data: Vec<u8> = (0..16).collect();
+    assert_reset_law(
+        SliceProducer::new(&data).optional(),
+        vec![None, Some(()), Some(())],
+    );
+}
+
+#[test]
+fn decorator_equivalence() {
+    use crate::{Counter, Ditto, Lazy};
+
+    assert_pipe_matches(Ditto::<u8>::default().bypass(), |i: u8| (i, i), 0..=255);
+    assert_pipe_matches(
+        Lazy::new(|i: u8| i / 2).optional(),
+        |i: Option<u8>| i.map(|i| i / 2),
+        vec![Some(1), None, Some(4)],
+    );
+    assert_pipes_equal(
+        Counter::new(0u32, 1).enumerate(),
+        Counter::new(0u32, 1).connect(Lazy::new(|i: u32| (i as usize, i))),
+        vec![(); 64],
+    );
+}
+
+#[test]
+#[should_panic(expected = "The pipes differ at input item 3: left = 6, right = 7")]
+fn pipes_equal_diagnostics() {
+    use crate::Lazy;
+
+    assert_pipes_equal(
+        Lazy::new(|i: u32| i * 2),
+        Lazy::new(|i: u32| if i < 3 { i * 2 } else { i * 2 + 1 }),
+        0..8,
+    );
+}
+
+#[test]
+#[should_panic(expected = "The pipe behaves differently after a reset at input item 0")]
+fn reset_law_violation() {
+    /// A counter whose reset is broken.
+    struct Broken(u32);
+
+    impl Pipe for Broken {
+        type InputItem = ();
+        type OutputItem = u32;
+
+        fn next(&mut self, _: ()) -> u32 {
+            self.0 += 1;
+            self.0
+        }
+    }
+
+    impl ResetablePipe for Broken {
+        fn reset(&mut self) {}
+    }
+
+    assert_reset_law(Broken(0), vec![(); 4]);
+}
@@ -0,0 +1,125 @@
+use crate::{Producer, PullStage};
+use std::collections::VecDeque;
+
+/// A pull stage that splits a stream of lines into tokens.
+///
+/// The upstream producer yields lines, and every call produces one token. The tokens of a line are buffered and the next line is only pulled once all tokens of the current line are produced. By default, the lines are split on whitespace, but a custom delimiter predicate can be used with [`split_by`](#method.split_by). Empty tokens are never produced, so empty lines or lines that only contain delimiters are skipped. Once the upstream producer is exhausted, `None` is produced.
+///
+/// Since this is a [`PullStage`](trait.PullStage.html), it's driven by wrapping it and the line producer in a [`Pulled`](struct.Pulled.html) adapter. Resetting the adapter drops the buffered tokens.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let lines = vec!["hello  world".to_string(), "".to_string(), " pipes ".to_string()];
+/// let tokens: Vec<String> = Pulled::new(PipeIter::new(lines.into_iter()), Tokenizer::new())
+///     .into_iter()
+///     .collect();
+///
+/// assert_eq!(vec!["hello", "world", "pipes"], tokens);
+/// ```
+pub struct Tokenizer<F = fn(char) -> bool>
+where
+    F: FnMut(char) -> bool,
+{
+    is_delimiter: F,
+    tokens: VecDeque<String>,
+}
+
+impl Tokenizer {
+    /// Create a new tokenizer that splits lines on whitespace.
+    pub fn new() -> Self {
+        Self::split_by(char::is_whitespace)
+    }
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F> Tokenizer<F>
+where
+    F: FnMut(char) -> bool,
+{
+    /// Create a new tokenizer that splits lines on the characters the predicate matches.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use iterpipes::*;
+    ///
+    /// let lines = vec!["a,b,,c".to_string()];
+    /// let tokens: Vec<String> = Pulled::new(
+    ///     PipeIter::new(lines.into_iter()),
+    ///     Tokenizer::split_by(|c| c == ','),
+    /// )
+    /// .into_iter()
+    /// .collect();
+    ///
+    /// assert_eq!(vec!["a", "b", "c"], tokens);
+    /// ```
+    pub fn split_by(is_delimiter: F) -> Self {
+        Self {
+            is_delimiter,
+            tokens: VecDeque::new(),
+        }
+    }
+
+    /// Return the number of buffered tokens of the current line.
+    pub fn buffered(&self) -> usize {
+        self.tokens.len()
+    }
+}
+
+impl<F> PullStage for Tokenizer<F>
+where
+    F: FnMut(char) -> bool,
+{
+    type InputItem = String;
+    type OutputItem = String;
+
+    fn pull<U>(&mut self, upstream: &mut U) -> Option<String>
+    where
+        U: Producer<Item = String>,
+    {
+        while self.tokens.is_empty() {
+            let line = upstream.produce()?;
+            let tokens = line
+                .split(&mut self.is_delimiter)
+                .filter(|token| !token.is_empty())
+                .map(String::from);
+            self.tokens.extend(tokens);
+        }
+        self.tokens.pop_front()
+    }
+
+    fn reset(&mut self) {
+        self.tokens.clear();
+    }
+}
+
+#[test]
+fn tokenizer() {
+    use crate::{Lazy, Pipe, PipeIter, Pulled, ResetablePipe, SliceProducer};
+
+    const TEXT: &str = "The quick brown fox\n\n   \njumps over\tthe\n  lazy dog.  \n";
+
+    let lines = PipeIter::new(TEXT.lines().map(String::from));
+    let tokens: Vec<String> = Pulled::new(lines, Tokenizer::new()).into_iter().collect();
+    let expected: Vec<&str> = TEXT.split_whitespace().collect();
+    assert_eq!(expected, tokens);
+
+    // Lines are only pulled once the tokens of the previous line are drained.
+    let lines: Vec<String> = TEXT.lines().map(String::from).collect();
+    let upstream =
+        SliceProducer::new(&lines).compose() >> Lazy::new(|line: Option<&String>| line.cloned());
+    let mut pipe = Pulled::new(upstream, Tokenizer::new());
+    assert_eq!(Some("The".to_string()), pipe.next(()));
+    assert_eq!(3, pipe.stage().buffered());
+    pipe.reset();
+    assert_eq!(0, pipe.stage().buffered());
+    assert_eq!(Some("The".to_string()), pipe.next(()));
+}
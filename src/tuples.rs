@@ -1,4 +1,5 @@
 use crate::{Pipe, ResetablePipe};
+use std::marker::PhantomData;
 
 macro_rules! impl_tuple {
     ($(
@@ -14,6 +15,19 @@ macro_rules! impl_tuple {
                 fn next(&mut self, input: Self::InputItem) -> Self::OutputItem {
                     ($(self.$idx.next(input.$idx),)+)
                 }
+
+                // The remaining items are zipped, so the surplus items of pipes with more remaining
+                // items than the others are dropped.
+                fn finish(&mut self) -> Vec<Self::OutputItem> {
+                    let mut tails = ($(self.$idx.finish().into_iter(),)+);
+                    let mut items = Vec::new();
+                    loop {
+                        items.push(($(match tails.$idx.next() {
+                            Some(item) => item,
+                            None => return items,
+                        },)+));
+                    }
+                }
             }
 
             impl<$($T: ResetablePipe,)+> ResetablePipe for ($($T,)+) {
@@ -129,3 +143,272 @@ impl_tuple! {
         (11) -> L
     }
 }
+
+/// A pipe that swaps the elements of a pair.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut swap: Swap<u8, char> = Swap::default();
+/// assert_eq!(('a', 1), swap.next((1, 'a')));
+/// ```
+pub struct Swap<A, B> {
+    items: PhantomData<(A, B)>,
+}
+
+impl<A, B> Default for Swap<A, B> {
+    fn default() -> Self {
+        Self { items: PhantomData }
+    }
+}
+
+impl<A, B> Pipe for Swap<A, B> {
+    type InputItem = (A, B);
+    type OutputItem = (B, A);
+
+    fn next(&mut self, (a, b): (A, B)) -> (B, A) {
+        (b, a)
+    }
+}
+
+impl<A, B> ResetablePipe for Swap<A, B> {
+    fn reset(&mut self) {}
+}
+
+/// A pipe that returns the first element of a pair.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut fst: Fst<u8, char> = Fst::default();
+/// assert_eq!(1, fst.next((1, 'a')));
+/// ```
+pub struct Fst<A, B> {
+    items: PhantomData<(A, B)>,
+}
+
+impl<A, B> Default for Fst<A, B> {
+    fn default() -> Self {
+        Self { items: PhantomData }
+    }
+}
+
+impl<A, B> Pipe for Fst<A, B> {
+    type InputItem = (A, B);
+    type OutputItem = A;
+
+    fn next(&mut self, (a, _): (A, B)) -> A {
+        a
+    }
+}
+
+impl<A, B> ResetablePipe for Fst<A, B> {
+    fn reset(&mut self) {}
+}
+
+/// A pipe that returns the second element of a pair.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut snd: Snd<u8, char> = Snd::default();
+/// assert_eq!('a', snd.next((1, 'a')));
+/// ```
+pub struct Snd<A, B> {
+    items: PhantomData<(A, B)>,
+}
+
+impl<A, B> Default for Snd<A, B> {
+    fn default() -> Self {
+        Self { items: PhantomData }
+    }
+}
+
+impl<A, B> Pipe for Snd<A, B> {
+    type InputItem = (A, B);
+    type OutputItem = B;
+
+    fn next(&mut self, (_, b): (A, B)) -> B {
+        b
+    }
+}
+
+impl<A, B> ResetablePipe for Snd<A, B> {
+    fn reset(&mut self) {}
+}
+
+/// A pipe that pairs every input item with a clone of a constant value.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipe = PairWith::new('a');
+/// assert_eq!((1, 'a'), pipe.next(1));
+/// assert_eq!((2, 'a'), pipe.next(2));
+/// ```
+pub struct PairWith<A, T: Clone> {
+    value: T,
+    item: PhantomData<A>,
+}
+
+impl<A, T: Clone> PairWith<A, T> {
+    /// Create a new pipe that pairs items with the given value.
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            item: PhantomData,
+        }
+    }
+}
+
+impl<A, T: Clone> Pipe for PairWith<A, T> {
+    type InputItem = A;
+    type OutputItem = (A, T);
+
+    fn next(&mut self, item: A) -> (A, T) {
+        (item, self.value.clone())
+    }
+}
+
+impl<A, T: Clone> ResetablePipe for PairWith<A, T> {
+    fn reset(&mut self) {}
+}
+
+/// A pipe that applies another pipe to the first element of a pair.
+///
+/// The second element is passed through untouched. This way, a pipe for single items can be applied to one channel of a stereo pair, for example. Resetting this pipe resets the wrapped pipe. When this pipe is finished, the wrapped pipe is finished too, but its remaining items are dropped since there are no second elements to pair them with.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipe = MapFst::new(Counter::new(0u8, 1).connect(Lazy::new(|i: u8| i * 2)))
+///     .compose()
+///     >> Swap::default();
+/// assert_eq!(('a', 0), pipe.next(((), 'a')));
+/// assert_eq!(('b', 2), pipe.next(((), 'b')));
+/// ```
+pub struct MapFst<P, B>
+where
+    P: Pipe,
+{
+    pipe: P,
+    item: PhantomData<B>,
+}
+
+impl<P: Pipe, B> MapFst<P, B> {
+    /// Create a new pipe that applies the given pipe to the first element.
+    pub fn new(pipe: P) -> Self {
+        Self {
+            pipe,
+            item: PhantomData,
+        }
+    }
+}
+
+impl<P: Pipe, B> Pipe for MapFst<P, B> {
+    type InputItem = (P::InputItem, B);
+    type OutputItem = (P::OutputItem, B);
+
+    fn next(&mut self, (a, b): (P::InputItem, B)) -> (P::OutputItem, B) {
+        (self.pipe.next(a), b)
+    }
+
+    fn finish(&mut self) -> Vec<(P::OutputItem, B)> {
+        // There are no second elements to pair the remaining items with.
+        self.pipe.finish();
+        Vec::new()
+    }
+}
+
+impl<P: ResetablePipe, B> ResetablePipe for MapFst<P, B> {
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A pipe that applies another pipe to the second element of a pair.
+///
+/// The first element is passed through untouched. Resetting this pipe resets the wrapped pipe. When this pipe is finished, the wrapped pipe is finished too, but its remaining items are dropped since there are no first elements to pair them with.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipe = MapSnd::new(Lazy::new(|i: f32| i * 0.5));
+/// assert_eq!((1.0, 0.5), pipe.next((1.0, 1.0)));
+/// ```
+pub struct MapSnd<A, P>
+where
+    P: Pipe,
+{
+    pipe: P,
+    item: PhantomData<A>,
+}
+
+impl<A, P: Pipe> MapSnd<A, P> {
+    /// Create a new pipe that applies the given pipe to the second element.
+    pub fn new(pipe: P) -> Self {
+        Self {
+            pipe,
+            item: PhantomData,
+        }
+    }
+}
+
+impl<A, P: Pipe> Pipe for MapSnd<A, P> {
+    type InputItem = (A, P::InputItem);
+    type OutputItem = (A, P::OutputItem);
+
+    fn next(&mut self, (a, b): (A, P::InputItem)) -> (A, P::OutputItem) {
+        (a, self.pipe.next(b))
+    }
+
+    fn finish(&mut self) -> Vec<(A, P::OutputItem)> {
+        // There are no first elements to pair the remaining items with.
+        self.pipe.finish();
+        Vec::new()
+    }
+}
+
+impl<A, P: ResetablePipe> ResetablePipe for MapSnd<A, P> {
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+#[test]
+fn tuple_utilities() {
+    use crate::{Counter, Ditto, Lazy};
+
+    // Process the left channel of a stereo pair and swap the channels.
+    let mut pipe = MapFst::new(Counter::new(0.0f32, 1.0).connect(Ditto::default())).compose()
+        >> Swap::default()
+        >> MapSnd::new(Lazy::new(|i: f32| i * 10.0));
+    assert_eq!((0.5, 0.0), pipe.next(((), 0.5)));
+    assert_eq!((0.25, 10.0), pipe.next(((), 0.25)));
+    pipe.reset();
+    assert_eq!((0.5, 0.0), pipe.next(((), 0.5)));
+
+    // Project the outputs of a tuple pipe.
+    let mut pipe = (Counter::new(0u8, 1), Ditto::default()).compose() >> Snd::default();
+    assert_eq!('a', pipe.next(((), 'a')));
+    let mut pipe = (Counter::new(0u8, 1), Ditto::<char>::default()).compose() >> Fst::default();
+    assert_eq!(0, pipe.next(((), 'a')));
+    assert_eq!(1, pipe.next(((), 'b')));
+
+    // Create a pair for a tuple pipe.
+    let mut pipe = PairWith::new(()).compose() >> (Lazy::new(|i: u8| i + 1), Counter::new(5u8, 1));
+    assert_eq!((2, 5), pipe.next(1));
+    assert_eq!((3, 6), pipe.next(2));
+}
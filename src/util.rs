@@ -1,4 +1,6 @@
 use crate::{Pipe, ResetablePipe};
+use std::collections::HashMap;
+use std::hash::Hash;
 use std::marker::PhantomData;
 
 /// A pipe that connects two other pipes together.
@@ -26,6 +28,36 @@ where
     pub fn new(pipe0: P0, pipe1: P1) -> Self {
         Connector { pipe0, pipe1 }
     }
+
+    /// Return a reference to the first pipe.
+    pub fn first(&self) -> &P0 {
+        &self.pipe0
+    }
+
+    /// Return a mutable reference to the first pipe.
+    pub fn first_mut(&mut self) -> &mut P0 {
+        &mut self.pipe0
+    }
+
+    /// Return a reference to the second pipe.
+    pub fn second(&self) -> &P1 {
+        &self.pipe1
+    }
+
+    /// Return a mutable reference to the second pipe.
+    pub fn second_mut(&mut self) -> &mut P1 {
+        &mut self.pipe1
+    }
+
+    /// Return mutable references to both pipes at once.
+    pub fn parts_mut(&mut self) -> (&mut P0, &mut P1) {
+        (&mut self.pipe0, &mut self.pipe1)
+    }
+
+    /// Take the connector apart and return both pipes.
+    pub fn into_parts(self) -> (P0, P1) {
+        (self.pipe0, self.pipe1)
+    }
 }
 
 impl<P0, P1> Pipe for Connector<P0, P1>
@@ -39,6 +71,18 @@ where
     fn next(&mut self, input: Self::InputItem) -> Self::OutputItem {
         self.pipe1.next(self.pipe0.next(input))
     }
+
+    fn finish(&mut self) -> Vec<Self::OutputItem> {
+        let pipe1 = &mut self.pipe1;
+        let mut items: Vec<P1::OutputItem> = self
+            .pipe0
+            .finish()
+            .into_iter()
+            .map(|item| pipe1.next(item))
+            .collect();
+        items.extend(self.pipe1.finish());
+        items
+    }
 }
 
 impl<P0, P1> ResetablePipe for Connector<P0, P1>
@@ -54,6 +98,8 @@ where
 
 /// A pipe that bypasses the effects of an internal pipe.
 ///
+/// When this pipe is finished, the internal pipe is finished too, but its remaining items are dropped since there are no input items to pair them with.
+///
 /// For more information, please see [the documentation of the `bypass` method](trait.Pipe.html#method.bypass).
 pub struct Bypass<P>
 where
@@ -85,6 +131,12 @@ where
     fn next(&mut self, input: P::InputItem) -> (P::InputItem, P::OutputItem) {
         (input.clone(), self.pipe.next(input))
     }
+
+    fn finish(&mut self) -> Vec<(P::InputItem, P::OutputItem)> {
+        // There are no input items to pair the remaining items with.
+        self.pipe.finish();
+        Vec::new()
+    }
 }
 
 impl<P> ResetablePipe for Bypass<P>
@@ -97,6 +149,68 @@ where
     }
 }
 
+/// A pipe that bypasses a part of its input item around an internal pipe.
+///
+/// When this pipe is finished, the internal pipe is finished too, but its remaining items are dropped since there are no kept parts to pair them with.
+///
+/// For more information, please see [the documentation of the `bypass_with` method](trait.Pipe.html#method.bypass_with).
+pub struct BypassWith<P, I, K, F>
+where
+    P: Pipe,
+    F: FnMut(I) -> (K, P::InputItem),
+{
+    pipe: P,
+    split: F,
+    input: PhantomData<I>,
+    kept: PhantomData<K>,
+}
+
+impl<P, I, K, F> BypassWith<P, I, K, F>
+where
+    P: Pipe,
+    F: FnMut(I) -> (K, P::InputItem),
+{
+    /// Create a new bypassed pipe with the given splitting function.
+    pub fn new(pipe: P, split: F) -> Self {
+        Self {
+            pipe,
+            split,
+            input: PhantomData,
+            kept: PhantomData,
+        }
+    }
+}
+
+impl<P, I, K, F> Pipe for BypassWith<P, I, K, F>
+where
+    P: Pipe,
+    F: FnMut(I) -> (K, P::InputItem),
+{
+    type InputItem = I;
+    type OutputItem = (K, P::OutputItem);
+
+    fn next(&mut self, input: I) -> (K, P::OutputItem) {
+        let (kept, forwarded) = (self.split)(input);
+        (kept, self.pipe.next(forwarded))
+    }
+
+    fn finish(&mut self) -> Vec<(K, P::OutputItem)> {
+        // There are no kept parts to pair the remaining items with.
+        self.pipe.finish();
+        Vec::new()
+    }
+}
+
+impl<P, I, K, F> ResetablePipe for BypassWith<P, I, K, F>
+where
+    P: ResetablePipe,
+    F: FnMut(I) -> (K, P::InputItem),
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
 /// A "lazily" create pipe with a mutable state.
 ///
 /// This pipe's behavior is defined by a callable object, for example a lambda expression, and can therefore be "lazily" created inline.
@@ -213,6 +327,189 @@ where
     fn reset(&mut self) {}
 }
 
+/// A pipe that is defined by an explicit state transition function.
+///
+/// The transition function receives the current state and the input item and returns the new state and the output item. The pipe stores the new state and returns the output item. Since the transition function doesn't need to capture any mutable state, it can be tested in isolation, and since the initial state is kept, resetting this pipe restores it.
+///
+/// The state is moved into the transition function, so it's lost if the transition function panics. In this case, the state machine panics when it's used again, until it's reset.
+///
+/// # Example
+///
+/// A debouncer that only changes its output after the input was stable for three calls:
+///
+/// ```
+/// use iterpipes::*;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Debounce {
+///     Stable(bool),
+///     Changing(bool, u8),
+/// }
+///
+/// fn transition(state: Debounce, input: bool) -> (Debounce, bool) {
+///     let state = match state {
+///         Debounce::Stable(value) if value == input => Debounce::Stable(value),
+///         Debounce::Stable(value) => Debounce::Changing(value, 1),
+///         Debounce::Changing(value, _) if value == input => Debounce::Stable(value),
+///         Debounce::Changing(_, 2) => Debounce::Stable(input),
+///         Debounce::Changing(value, count) => Debounce::Changing(value, count + 1),
+///     };
+///     let output = match state {
+///         Debounce::Stable(value) | Debounce::Changing(value, _) => value,
+///     };
+///     (state, output)
+/// }
+///
+/// // The transition function can be tested on its own.
+/// assert_eq!((Debounce::Changing(false, 1), false), transition(Debounce::Stable(false), true));
+///
+/// let mut debouncer = StateMachine::new(Debounce::Stable(false), transition);
+/// let input = [true, false, true, true, true, true, false];
+/// let output: Vec<bool> = input.iter().map(|i| debouncer.next(*i)).collect();
+/// assert_eq!(vec![false, false, false, false, true, true, true], output);
+/// ```
+pub struct StateMachine<S, I, O, F>
+where
+    S: Clone,
+    F: FnMut(S, I) -> (S, O),
+{
+    initial: S,
+    state: Option<S>,
+    transition: F,
+    input: PhantomData<I>,
+    output: PhantomData<O>,
+}
+
+/// The panic message of a state machine whose state was lost.
+const LOST_STATE: &str = "The transition function of the state machine panicked";
+
+impl<S, I, O, F> StateMachine<S, I, O, F>
+where
+    S: Clone,
+    F: FnMut(S, I) -> (S, O),
+{
+    /// Create a new state machine with the given initial state and transition function.
+    pub fn new(initial: S, transition: F) -> Self {
+        StateMachine {
+            state: Some(initial.clone()),
+            initial,
+            transition,
+            input: PhantomData,
+            output: PhantomData,
+        }
+    }
+
+    /// Return the current state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a previous transition panicked and the state machine wasn't reset since.
+    pub fn state(&self) -> &S {
+        self.state.as_ref().expect(LOST_STATE)
+    }
+}
+
+impl<S, I, O, F> Pipe for StateMachine<S, I, O, F>
+where
+    S: Clone,
+    F: FnMut(S, I) -> (S, O),
+{
+    type InputItem = I;
+    type OutputItem = O;
+
+    fn next(&mut self, input: I) -> O {
+        let state = self.state.take().expect(LOST_STATE);
+        let (state, output) = (self.transition)(state, input);
+        self.state = Some(state);
+        output
+    }
+}
+
+impl<S, I, O, F> ResetablePipe for StateMachine<S, I, O, F>
+where
+    S: Clone,
+    F: FnMut(S, I) -> (S, O),
+{
+    fn reset(&mut self) {
+        self.state = Some(self.initial.clone());
+    }
+}
+
+/// A producer pipe that generates its items from a seed state.
+///
+/// Every call passes a mutable reference to the state to the function and returns its result. This is the pipe equivalent of [`std::iter::from_fn`](https://doc.rust-lang.org/std/iter/fn.from_fn.html), but since the initial seed is kept, resetting this pipe restores it and the same items are generated again. This makes it possible to write resetable producers inline, without defining a new struct.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut fibonacci = Unfold::new((0u32, 1u32), |(a, b): &mut (u32, u32)| {
+///     let next = *a;
+///     *a = *b;
+///     *b = b.checked_add(next)?;
+///     Some(next)
+/// });
+///
+/// let numbers: Vec<u32> = (0..8).map(|_| fibonacci.next(()).unwrap()).collect();
+/// assert_eq!(vec![0, 1, 1, 2, 3, 5, 8, 13], numbers);
+///
+/// fibonacci.reset();
+/// assert_eq!(Some(0), fibonacci.next(()));
+/// ```
+pub struct Unfold<S, T, F>
+where
+    S: Clone,
+    F: FnMut(&mut S) -> Option<T>,
+{
+    seed: S,
+    state: S,
+    function: F,
+}
+
+impl<S, T, F> Unfold<S, T, F>
+where
+    S: Clone,
+    F: FnMut(&mut S) -> Option<T>,
+{
+    /// Create a new unfolding pipe with the given seed and function.
+    pub fn new(seed: S, function: F) -> Self {
+        Unfold {
+            state: seed.clone(),
+            seed,
+            function,
+        }
+    }
+
+    /// Return the current state.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S, T, F> Pipe for Unfold<S, T, F>
+where
+    S: Clone,
+    F: FnMut(&mut S) -> Option<T>,
+{
+    type InputItem = ();
+    type OutputItem = Option<T>;
+
+    fn next(&mut self, _: ()) -> Option<T> {
+        (self.function)(&mut self.state)
+    }
+}
+
+impl<S, T, F> ResetablePipe for Unfold<S, T, F>
+where
+    S: Clone,
+    F: FnMut(&mut S) -> Option<T>,
+{
+    fn reset(&mut self) {
+        self.state = self.seed.clone();
+    }
+}
+
 /// A pipe that wraps another pipe's IO in an `Option`.
 ///
 /// For more information, please see [the documentation of the `optional` method](trait.Pipe.html#method.optional).
@@ -240,6 +537,10 @@ where
     fn next(&mut self, item: Option<P::InputItem>) -> Option<P::OutputItem> {
         item.map(|item| self.pipe.next(item))
     }
+
+    fn finish(&mut self) -> Vec<Option<P::OutputItem>> {
+        self.pipe.finish().into_iter().map(Some).collect()
+    }
 }
 
 impl<P> ResetablePipe for Optional<P>
@@ -251,135 +552,1550 @@ where
     }
 }
 
-/// A pipe that enumerates the output items of another pipe.
-///
-/// The inputs of this pipe are the same as the wrapped ones, but it's output item is a tuple of an index and the wrapped pipe's output. The index starts with zero and counts up for every produces output item.
+/// A pipe that unwraps the optional output items of another pipe or returns a default value.
 ///
-/// For more information, please see [the documentation of the `enumerate` method](trait.Pipe.html#method.enumerate).
-pub struct Enumerate<P>
+/// For more information, please see [the documentation of the `unwrap_or` method](trait.Pipe.html#method.unwrap_or).
+pub struct UnwrapOr<P, T>
 where
-    P: Pipe,
+    P: Pipe<OutputItem = Option<T>>,
+    T: Clone,
 {
     pipe: P,
-    progress: usize,
+    default: T,
 }
 
-impl<P: Pipe> Enumerate<P> {
-    /// Create a new enumerating pipe.
-    pub fn new(pipe: P) -> Self {
-        Enumerate { pipe, progress: 0 }
+impl<P, T> UnwrapOr<P, T>
+where
+    P: Pipe<OutputItem = Option<T>>,
+    T: Clone,
+{
+    /// Create a new unwrapping pipe.
+    pub fn new(pipe: P, default: T) -> Self {
+        Self { pipe, default }
     }
 }
 
-impl<P: Pipe> Pipe for Enumerate<P> {
+impl<P, T> Pipe for UnwrapOr<P, T>
+where
+    P: Pipe<OutputItem = Option<T>>,
+    T: Clone,
+{
     type InputItem = P::InputItem;
-    type OutputItem = (usize, P::OutputItem);
+    type OutputItem = T;
 
-    fn next(&mut self, item: P::InputItem) -> (usize, P::OutputItem) {
-        let next_item = self.pipe.next(item);
-        let index = self.progress;
-        self.progress += 1;
-        (index, next_item)
+    fn next(&mut self, item: P::InputItem) -> T {
+        match self.pipe.next(item) {
+            Some(item) => item,
+            None => self.default.clone(),
+        }
+    }
+
+    fn finish(&mut self) -> Vec<T> {
+        let default = &self.default;
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(|item| item.unwrap_or_else(|| default.clone()))
+            .collect()
     }
 }
 
-impl<P: ResetablePipe> ResetablePipe for Enumerate<P> {
+impl<P, T> ResetablePipe for UnwrapOr<P, T>
+where
+    P: ResetablePipe<OutputItem = Option<T>>,
+    T: Clone,
+{
     fn reset(&mut self) {
         self.pipe.reset();
-        self.progress = 0;
     }
 }
 
-/// A continous counter.
-///
-/// This pipe has an counter and a delta value. Every time `next` is called, the current counter value is returned and the delta is added to the counter. It also knows it's starting value and can therefore be reseted.
-///
-/// # Example
-///
-/// ```
-/// use iterpipes::*;
+/// A pipe that unwraps the optional output items of another pipe or returns the default value of the type.
 ///
-/// let mut counter: Counter<u8> = Counter::new(1, 2);
-/// assert_eq!(1, counter.next(()));
-/// assert_eq!(3, counter.next(()));
-/// ```
-pub struct Counter<T>
+/// For more information, please see [the documentation of the `unwrap_or_default` method](trait.Pipe.html#method.unwrap_or_default).
+pub struct UnwrapOrDefault<P>
 where
-    T: std::ops::AddAssign<T> + Copy,
+    P: Pipe,
 {
-    starting_value: T,
-    delta: T,
-    counter: T,
+    pipe: P,
 }
 
-impl<T> Counter<T>
-where
-    T: std::ops::AddAssign<T> + Copy,
-{
-    pub fn new(starting_value: T, delta: T) -> Self {
-        Self {
-            starting_value,
-            delta,
-            counter: starting_value,
-        }
+impl<P: Pipe> UnwrapOrDefault<P> {
+    /// Create a new unwrapping pipe.
+    pub fn new(pipe: P) -> Self {
+        Self { pipe }
     }
 }
 
-impl<T> Pipe for Counter<T>
+impl<P, T> Pipe for UnwrapOrDefault<P>
 where
-    T: std::ops::AddAssign<T> + Copy,
+    P: Pipe<OutputItem = Option<T>>,
+    T: Default,
 {
-    type InputItem = ();
+    type InputItem = P::InputItem;
     type OutputItem = T;
 
-    fn next(&mut self, _: ()) -> T {
-        let item = self.counter;
-        self.counter += self.delta;
-        item
+    fn next(&mut self, item: P::InputItem) -> T {
+        self.pipe.next(item).unwrap_or_default()
+    }
+
+    fn finish(&mut self) -> Vec<T> {
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect()
     }
 }
 
-impl<T> ResetablePipe for Counter<T>
+impl<P, T> ResetablePipe for UnwrapOrDefault<P>
 where
-    T: std::ops::AddAssign<T> + Copy,
+    P: ResetablePipe<OutputItem = Option<T>>,
+    T: Default,
 {
     fn reset(&mut self) {
-        self.counter = self.starting_value;
+        self.pipe.reset();
     }
 }
 
-/// A simple forwarding pipe.
-///
-/// This pipe takes an input item and immediately emits it again.
+/// A pipe that unwraps the optional output items of another pipe or computes a value from a closure.
 ///
-/// # Example
-///
-/// ```
-/// use iterpipes::*;
+/// Note that resetting this pipe only resets the wrapped pipe, not the state of the closure.
 ///
-/// let mut ditto: Ditto<u8> = Ditto::default();
-///
-/// assert_eq!(ditto.next(42), 42);
-/// ```
-pub struct Ditto<T> {
-    item: PhantomData<T>,
+/// For more information, please see [the documentation of the `unwrap_or_else` method](trait.Pipe.html#method.unwrap_or_else).
+pub struct UnwrapOrElse<P, F>
+where
+    P: Pipe,
+{
+    pipe: P,
+    function: F,
 }
 
-impl<T> Default for Ditto<T> {
-    fn default() -> Self {
-        Self { item: PhantomData }
+impl<P: Pipe, F> UnwrapOrElse<P, F> {
+    /// Create a new unwrapping pipe.
+    pub fn new(pipe: P, function: F) -> Self {
+        Self { pipe, function }
     }
 }
 
-impl<T> Pipe for Ditto<T> {
-    type InputItem = T;
+impl<P, T, F> Pipe for UnwrapOrElse<P, F>
+where
+    P: Pipe<OutputItem = Option<T>>,
+    F: FnMut() -> T,
+{
+    type InputItem = P::InputItem;
     type OutputItem = T;
 
-    fn next(&mut self, item: T) -> T {
-        item
+    fn next(&mut self, item: P::InputItem) -> T {
+        match self.pipe.next(item) {
+            Some(item) => item,
+            None => (self.function)(),
+        }
+    }
+
+    fn finish(&mut self) -> Vec<T> {
+        let function = &mut self.function;
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(|item| item.unwrap_or_else(&mut *function))
+            .collect()
     }
 }
 
-impl<T> ResetablePipe for Ditto<T> {
-    fn reset(&mut self) {}
+impl<P, T, F> ResetablePipe for UnwrapOrElse<P, F>
+where
+    P: ResetablePipe<OutputItem = Option<T>>,
+    F: FnMut() -> T,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A pipe that enumerates the output items of another pipe.
+///
+/// The inputs of this pipe are the same as the wrapped ones, but it's output item is a tuple of an index and the wrapped pipe's output. The index starts with zero and counts up for every produces output item.
+///
+/// For more information, please see [the documentation of the `enumerate` method](trait.Pipe.html#method.enumerate).
+pub struct Enumerate<P>
+where
+    P: Pipe,
+{
+    pipe: P,
+    progress: usize,
+}
+
+impl<P: Pipe> Enumerate<P> {
+    /// Create a new enumerating pipe.
+    pub fn new(pipe: P) -> Self {
+        Enumerate { pipe, progress: 0 }
+    }
+}
+
+impl<P: Pipe> Pipe for Enumerate<P> {
+    type InputItem = P::InputItem;
+    type OutputItem = (usize, P::OutputItem);
+
+    fn next(&mut self, item: P::InputItem) -> (usize, P::OutputItem) {
+        let next_item = self.pipe.next(item);
+        let index = self.progress;
+        self.progress += 1;
+        (index, next_item)
+    }
+
+    fn finish(&mut self) -> Vec<(usize, P::OutputItem)> {
+        let items = self.pipe.finish();
+        let start = self.progress;
+        self.progress += items.len();
+        (start..).zip(items).collect()
+    }
+}
+
+impl<P: ResetablePipe> ResetablePipe for Enumerate<P> {
+    fn reset(&mut self) {
+        self.pipe.reset();
+        self.progress = 0;
+    }
+}
+
+/// A pipe that converts the output items of another pipe using `From`.
+///
+/// For more information, please see [the documentation of the `convert` method](trait.Pipe.html#method.convert).
+pub struct Convert<P, U>
+where
+    P: Pipe,
+    U: From<P::OutputItem>,
+{
+    pipe: P,
+    output: PhantomData<U>,
+}
+
+impl<P, U> Convert<P, U>
+where
+    P: Pipe,
+    U: From<P::OutputItem>,
+{
+    /// Create a new converting pipe.
+    pub fn new(pipe: P) -> Self {
+        Self {
+            pipe,
+            output: PhantomData,
+        }
+    }
+}
+
+impl<P, U> Pipe for Convert<P, U>
+where
+    P: Pipe,
+    U: From<P::OutputItem>,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = U;
+
+    fn next(&mut self, item: P::InputItem) -> U {
+        U::from(self.pipe.next(item))
+    }
+
+    fn finish(&mut self) -> Vec<U> {
+        self.pipe.finish().into_iter().map(U::from).collect()
+    }
+}
+
+impl<P, U> ResetablePipe for Convert<P, U>
+where
+    P: ResetablePipe,
+    U: From<P::OutputItem>,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A pipe that converts its input items into the input items of another pipe using `From`.
+///
+/// For more information, please see [the documentation of the `convert_input` method](trait.Pipe.html#method.convert_input).
+pub struct ConvertInput<P, U>
+where
+    P: Pipe,
+    P::InputItem: From<U>,
+{
+    pipe: P,
+    input: PhantomData<U>,
+}
+
+impl<P, U> ConvertInput<P, U>
+where
+    P: Pipe,
+    P::InputItem: From<U>,
+{
+    /// Create a new input converting pipe.
+    pub fn new(pipe: P) -> Self {
+        Self {
+            pipe,
+            input: PhantomData,
+        }
+    }
+}
+
+impl<P, U> Pipe for ConvertInput<P, U>
+where
+    P: Pipe,
+    P::InputItem: From<U>,
+{
+    type InputItem = U;
+    type OutputItem = P::OutputItem;
+
+    fn next(&mut self, item: U) -> P::OutputItem {
+        self.pipe.next(P::InputItem::from(item))
+    }
+
+    fn finish(&mut self) -> Vec<P::OutputItem> {
+        self.pipe.finish()
+    }
+}
+
+impl<P, U> ResetablePipe for ConvertInput<P, U>
+where
+    P: ResetablePipe,
+    P::InputItem: From<U>,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A pipe that pairs every output item of another pipe with its predecessor.
+///
+/// The output item is `Some((previous, current))`, except for the first call, where there is no predecessor and `None` is returned. Resetting this pipe resets the wrapped pipe and forgets the predecessor.
+///
+/// For more information, please see [the documentation of the `pairwise` method](trait.Pipe.html#method.pairwise).
+pub struct Pairwise<P>
+where
+    P: Pipe,
+    P::OutputItem: Clone,
+{
+    pipe: P,
+    previous: Option<P::OutputItem>,
+}
+
+impl<P> Pairwise<P>
+where
+    P: Pipe,
+    P::OutputItem: Clone,
+{
+    /// Create a new pairing pipe.
+    pub fn new(pipe: P) -> Self {
+        Self {
+            pipe,
+            previous: None,
+        }
+    }
+}
+
+impl<P> Pipe for Pairwise<P>
+where
+    P: Pipe,
+    P::OutputItem: Clone,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = Option<(P::OutputItem, P::OutputItem)>;
+
+    fn next(&mut self, item: P::InputItem) -> Option<(P::OutputItem, P::OutputItem)> {
+        let current = self.pipe.next(item);
+        self.previous
+            .replace(current.clone())
+            .map(|previous| (previous, current))
+    }
+
+    fn finish(&mut self) -> Vec<Option<(P::OutputItem, P::OutputItem)>> {
+        let previous = &mut self.previous;
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(|current| {
+                previous
+                    .replace(current.clone())
+                    .map(|previous| (previous, current))
+            })
+            .collect()
+    }
+}
+
+impl<P> ResetablePipe for Pairwise<P>
+where
+    P: ResetablePipe,
+    P::OutputItem: Clone,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+        self.previous = None;
+    }
+}
+
+/// A pipe that pairs every output item of another pipe with its predecessor, starting with an initial predecessor.
+///
+/// The output item is a tuple of the previous and the current output item. On the first call, the initial value is used as the predecessor. Resetting this pipe resets the wrapped pipe and restores the initial predecessor.
+///
+/// For more information, please see [the documentation of the `pairwise_with` method](trait.Pipe.html#method.pairwise_with).
+pub struct PairwiseWith<P>
+where
+    P: Pipe,
+    P::OutputItem: Clone,
+{
+    pipe: P,
+    initial: P::OutputItem,
+    previous: P::OutputItem,
+}
+
+impl<P> PairwiseWith<P>
+where
+    P: Pipe,
+    P::OutputItem: Clone,
+{
+    /// Create a new pairing pipe with the given initial predecessor.
+    pub fn new(pipe: P, initial: P::OutputItem) -> Self {
+        Self {
+            pipe,
+            previous: initial.clone(),
+            initial,
+        }
+    }
+}
+
+impl<P> Pipe for PairwiseWith<P>
+where
+    P: Pipe,
+    P::OutputItem: Clone,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = (P::OutputItem, P::OutputItem);
+
+    fn next(&mut self, item: P::InputItem) -> (P::OutputItem, P::OutputItem) {
+        let current = self.pipe.next(item);
+        let previous = std::mem::replace(&mut self.previous, current.clone());
+        (previous, current)
+    }
+
+    fn finish(&mut self) -> Vec<(P::OutputItem, P::OutputItem)> {
+        let previous = &mut self.previous;
+        self.pipe
+            .finish()
+            .into_iter()
+            .map(|current| (std::mem::replace(previous, current.clone()), current))
+            .collect()
+    }
+}
+
+impl<P> ResetablePipe for PairwiseWith<P>
+where
+    P: ResetablePipe,
+    P::OutputItem: Clone,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+        self.previous = self.initial.clone();
+    }
+}
+
+/// A producer pipe that can look at its next output item without consuming it.
+///
+/// [`peek`](#method.peek) pulls the next output item from the wrapped pipe and stashes it, and the next call to `next` returns the stashed item instead of polling the wrapped pipe again. Peeking several times in a row only polls the wrapped pipe once. This mirrors `Iterator::peekable`: If the output item is an `Option` and the peeked item is `None`, the next call to `next` returns this `None` without polling the wrapped pipe, so it doesn't need to be fused.
+///
+/// Resetting this pipe resets the wrapped pipe and drops the stashed item. When the pipe is finished, a stashed item is returned before the remaining items of the wrapped pipe.
+///
+/// For more information, please see [the documentation of the `peekable` method](trait.Pipe.html#method.peekable).
+pub struct Peekable<P>
+where
+    P: Pipe<InputItem = ()>,
+{
+    pipe: P,
+    peeked: Option<P::OutputItem>,
+}
+
+impl<P> Peekable<P>
+where
+    P: Pipe<InputItem = ()>,
+{
+    /// Create a new peekable pipe.
+    pub fn new(pipe: P) -> Self {
+        Self { pipe, peeked: None }
+    }
+
+    /// Return a reference to the next output item without consuming it.
+    pub fn peek(&mut self) -> &P::OutputItem {
+        let pipe = &mut self.pipe;
+        self.peeked.get_or_insert_with(|| pipe.next(()))
+    }
+}
+
+impl<P> Pipe for Peekable<P>
+where
+    P: Pipe<InputItem = ()>,
+{
+    type InputItem = ();
+    type OutputItem = P::OutputItem;
+
+    fn next(&mut self, _: ()) -> P::OutputItem {
+        match self.peeked.take() {
+            Some(item) => item,
+            None => self.pipe.next(()),
+        }
+    }
+
+    fn finish(&mut self) -> Vec<P::OutputItem> {
+        let mut items: Vec<P::OutputItem> = self.peeked.take().into_iter().collect();
+        items.extend(self.pipe.finish());
+        items
+    }
+}
+
+impl<P> ResetablePipe for Peekable<P>
+where
+    P: ResetablePipe<InputItem = ()>,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+        self.peeked = None;
+    }
+}
+
+/// A pipe that caches the output items of another pipe.
+///
+/// The wrapped pipe is only invoked if the input item wasn't seen before. Otherwise, a clone of the cached output item is returned. This obviously only makes sense if the wrapped pipe is pure, i.e. if its output only depends on its input.
+///
+/// The cache may have a capacity. If the cache is full and a new item needs to be cached, the whole cache is cleared before the new item is inserted.
+///
+/// Resetting this pipe resets the wrapped pipe and clears the cache as well as the hit and miss counters. If the cache should be kept, use [`reset_keep_cache`](#method.reset_keep_cache) instead.
+///
+/// For more information, please see [the documentation of the `memoize` method](trait.Pipe.html#method.memoize).
+pub struct Memoize<P>
+where
+    P: Pipe,
+    P::InputItem: Eq + Hash + Clone,
+    P::OutputItem: Clone,
+{
+    pipe: P,
+    cache: HashMap<P::InputItem, P::OutputItem>,
+    capacity: Option<usize>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<P> Memoize<P>
+where
+    P: Pipe,
+    P::InputItem: Eq + Hash + Clone,
+    P::OutputItem: Clone,
+{
+    /// Create a new memoizing pipe with an unbounded cache.
+    pub fn new(pipe: P) -> Self {
+        Self {
+            pipe,
+            cache: HashMap::new(),
+            capacity: None,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Create a new memoizing pipe whose cache holds at most `capacity` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_capacity(pipe: P, capacity: usize) -> Self {
+        assert!(capacity != 0, "The capacity must not be zero");
+        Self {
+            pipe,
+            cache: HashMap::with_capacity(capacity),
+            capacity: Some(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Return the number of input items that were answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Return the number of input items that had to be processed by the wrapped pipe.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Clear the cache.
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+    }
+}
+
+impl<P> Memoize<P>
+where
+    P: ResetablePipe,
+    P::InputItem: Eq + Hash + Clone,
+    P::OutputItem: Clone,
+{
+    /// Reset the wrapped pipe, but keep the cache and the counters.
+    pub fn reset_keep_cache(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+impl<P> Pipe for Memoize<P>
+where
+    P: Pipe,
+    P::InputItem: Eq + Hash + Clone,
+    P::OutputItem: Clone,
+{
+    type InputItem = P::InputItem;
+    type OutputItem = P::OutputItem;
+
+    fn next(&mut self, item: P::InputItem) -> P::OutputItem {
+        if let Some(output) = self.cache.get(&item) {
+            self.hits += 1;
+            return output.clone();
+        }
+
+        self.misses += 1;
+        let output = self.pipe.next(item.clone());
+        if let Some(capacity) = self.capacity {
+            if self.cache.len() >= capacity {
+                self.cache.clear();
+            }
+        }
+        self.cache.insert(item, output.clone());
+        output
+    }
+
+    fn finish(&mut self) -> Vec<P::OutputItem> {
+        self.pipe.finish()
+    }
+}
+
+impl<P> ResetablePipe for Memoize<P>
+where
+    P: ResetablePipe,
+    P::InputItem: Eq + Hash + Clone,
+    P::OutputItem: Clone,
+{
+    fn reset(&mut self) {
+        self.pipe.reset();
+        self.cache.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+/// A pipe that passes items only if a gate is open.
+///
+/// The input item of this pipe is a tuple of an item and a boolean gate. If the gate is open (`true`), the item is returned. If it's closed (`false`), a clone of the configured "closed" value is returned instead, for example silence.
+///
+/// If an existing pipe should only be advanced while the gate is open, use the [`gated`](trait.Pipe.html#method.gated) decorator instead.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut gate: Gate<f32> = Gate::new(0.0);
+/// assert_eq!(0.5, gate.next((0.5, true)));
+/// assert_eq!(0.0, gate.next((0.5, false)));
+/// ```
+pub struct Gate<T: Clone> {
+    closed: T,
+}
+
+impl<T: Clone> Gate<T> {
+    /// Create a new gate that returns the given value while it's closed.
+    pub fn new(closed: T) -> Self {
+        Self { closed }
+    }
+}
+
+impl<T: Clone + Default> Default for Gate<T> {
+    /// Create a new gate that returns the default value while it's closed.
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Clone> Pipe for Gate<T> {
+    type InputItem = (T, bool);
+    type OutputItem = T;
+
+    fn next(&mut self, (item, open): (T, bool)) -> T {
+        if open {
+            item
+        } else {
+            self.closed.clone()
+        }
+    }
+}
+
+impl<T: Clone> ResetablePipe for Gate<T> {
+    fn reset(&mut self) {}
+}
+
+/// A pipe that only advances another pipe while a gate is open.
+///
+/// The input item of this pipe is a tuple of the wrapped pipe's input item and a boolean gate. If the gate is open (`true`), the item is processed by the wrapped pipe and `Some(output)` is returned. If it's closed (`false`), the wrapped pipe is not invoked at all and `None` is returned.
+///
+/// For more information, please see [the documentation of the `gated` method](trait.Pipe.html#method.gated).
+pub struct Gated<P>
+where
+    P: Pipe,
+{
+    pipe: P,
+}
+
+impl<P: Pipe> Gated<P> {
+    /// Create a new gated pipe.
+    pub fn new(pipe: P) -> Self {
+        Self { pipe }
+    }
+}
+
+impl<P: Pipe> Pipe for Gated<P> {
+    type InputItem = (P::InputItem, bool);
+    type OutputItem = Option<P::OutputItem>;
+
+    fn next(&mut self, (item, open): (P::InputItem, bool)) -> Option<P::OutputItem> {
+        if open {
+            Some(self.pipe.next(item))
+        } else {
+            None
+        }
+    }
+
+    fn finish(&mut self) -> Vec<Option<P::OutputItem>> {
+        self.pipe.finish().into_iter().map(Some).collect()
+    }
+}
+
+impl<P: ResetablePipe> ResetablePipe for Gated<P> {
+    fn reset(&mut self) {
+        self.pipe.reset();
+    }
+}
+
+/// A pipe that captures a value whenever it's triggered and holds it otherwise.
+///
+/// The input item of this pipe is a tuple of a value and a boolean trigger. If the trigger is set (`true`), the value is captured and returned. Otherwise, the value is ignored and the most recently captured value is returned, or the initial value if nothing was captured yet. This models clocked registers or parameter snapshots at block boundaries.
+///
+/// Resetting this pipe restores the initial value.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut latch = Latch::new(0);
+/// assert_eq!(0, latch.next((1, false)));
+/// assert_eq!(2, latch.next((2, true)));
+/// assert_eq!(2, latch.next((3, false)));
+/// ```
+pub struct Latch<T: Clone> {
+    initial: T,
+    value: T,
+}
+
+impl<T: Clone> Latch<T> {
+    /// Create a new latch that returns the given value until it's triggered for the first time.
+    pub fn new(initial: T) -> Self {
+        Self {
+            value: initial.clone(),
+            initial,
+        }
+    }
+
+    /// Return the currently captured value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Clone> Pipe for Latch<T> {
+    type InputItem = (T, bool);
+    type OutputItem = T;
+
+    fn next(&mut self, (item, trigger): (T, bool)) -> T {
+        if trigger {
+            self.value = item;
+        }
+        self.value.clone()
+    }
+}
+
+impl<T: Clone> ResetablePipe for Latch<T> {
+    fn reset(&mut self) {
+        self.value = self.initial.clone();
+    }
+}
+
+/// A continous counter.
+///
+/// This pipe has an counter and a delta value. Every time `next` is called, the current counter value is returned and the delta is added to the counter. It also knows it's starting value and can therefore be reseted.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut counter: Counter<u8> = Counter::new(1, 2);
+/// assert_eq!(1, counter.next(()));
+/// assert_eq!(3, counter.next(()));
+/// ```
+pub struct Counter<T>
+where
+    T: std::ops::AddAssign<T> + Copy,
+{
+    starting_value: T,
+    delta: T,
+    counter: T,
+}
+
+impl<T> Counter<T>
+where
+    T: std::ops::AddAssign<T> + Copy,
+{
+    pub fn new(starting_value: T, delta: T) -> Self {
+        Self {
+            starting_value,
+            delta,
+            counter: starting_value,
+        }
+    }
+}
+
+impl<T> Pipe for Counter<T>
+where
+    T: std::ops::AddAssign<T> + Copy,
+{
+    type InputItem = ();
+    type OutputItem = T;
+
+    fn next(&mut self, _: ()) -> T {
+        let item = self.counter;
+        self.counter += self.delta;
+        item
+    }
+}
+
+impl<T> ResetablePipe for Counter<T>
+where
+    T: std::ops::AddAssign<T> + Copy,
+{
+    fn reset(&mut self) {
+        self.counter = self.starting_value;
+    }
+}
+
+/// A counter that stops at an upper bound.
+///
+/// This pipe behaves like the [`Counter`](struct.Counter.html), but it also has an end value. As long as the counter value is less than the end value, `Some(value)` is returned and the delta is added to the counter. Once the counter value is greater than or equal to the end value, `None` is returned. If the delta doesn't divide the range evenly, the counter therefore stops at the first value that overshoots the end.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut counter: BoundedCounter<u8> = BoundedCounter::new(1, 6, 2);
+/// assert_eq!(Some(1), counter.next(()));
+/// assert_eq!(Some(3), counter.next(()));
+/// assert_eq!(Some(5), counter.next(()));
+/// assert_eq!(None, counter.next(()));
+///
+/// counter.reset();
+/// assert_eq!(Some(1), counter.next(()));
+/// ```
+pub struct BoundedCounter<T>
+where
+    T: std::ops::AddAssign<T> + PartialOrd + Copy,
+{
+    starting_value: T,
+    end: T,
+    delta: T,
+    counter: T,
+}
+
+impl<T> BoundedCounter<T>
+where
+    T: std::ops::AddAssign<T> + PartialOrd + Copy,
+{
+    pub fn new(starting_value: T, end: T, delta: T) -> Self {
+        Self {
+            starting_value,
+            end,
+            delta,
+            counter: starting_value,
+        }
+    }
+}
+
+impl<T> Pipe for BoundedCounter<T>
+where
+    T: std::ops::AddAssign<T> + PartialOrd + Copy,
+{
+    type InputItem = ();
+    type OutputItem = Option<T>;
+
+    fn next(&mut self, _: ()) -> Option<T> {
+        if self.counter >= self.end {
+            return None;
+        }
+        let item = self.counter;
+        self.counter += self.delta;
+        Some(item)
+    }
+}
+
+impl<T> ResetablePipe for BoundedCounter<T>
+where
+    T: std::ops::AddAssign<T> + PartialOrd + Copy,
+{
+    fn reset(&mut self) {
+        self.counter = self.starting_value;
+    }
+}
+
+/// A pipe that always returns a clone of the same value.
+///
+/// This is useful as the "second input" of a tuple pipe, for example to pair a stream of samples with a constant gain.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipe = (Ditto::<f32>::default(), Const::new(0.5))
+///     .connect(Lazy::new(|(sample, gain): (f32, f32)| sample * gain));
+///
+/// assert_eq!(1.0, pipe.next((2.0, ())));
+/// assert_eq!(-2.0, pipe.next((-4.0, ())));
+/// ```
+pub struct Const<T: Clone> {
+    value: T,
+}
+
+impl<T: Clone> Const<T> {
+    /// Create a new constant pipe.
+    pub fn new(value: T) -> Self {
+        Self { value }
+    }
+}
+
+impl<T: Clone> Pipe for Const<T> {
+    type InputItem = ();
+    type OutputItem = T;
+
+    fn next(&mut self, _: ()) -> T {
+        self.value.clone()
+    }
+}
+
+impl<T: Clone> ResetablePipe for Const<T> {
+    fn reset(&mut self) {}
+}
+
+/// An alias for the [`Const`](struct.Const.html) pipe, named after `std::iter::repeat`.
+pub type Repeat<T> = Const<T>;
+
+/// A pipe that always returns the default value of a type.
+///
+/// In contrast to the [`Const`](struct.Const.html) pipe, the type doesn't need to be `Clone` since a new default value is created every time.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut pipe: DefaultValue<Vec<u8>> = DefaultValue::default();
+/// assert_eq!(Vec::<u8>::new(), pipe.next(()));
+/// ```
+pub struct DefaultValue<T: Default> {
+    item: PhantomData<T>,
+}
+
+impl<T: Default> Default for DefaultValue<T> {
+    fn default() -> Self {
+        Self { item: PhantomData }
+    }
+}
+
+impl<T: Default> Pipe for DefaultValue<T> {
+    type InputItem = ();
+    type OutputItem = T;
+
+    fn next(&mut self, _: ()) -> T {
+        T::default()
+    }
+}
+
+impl<T: Default> ResetablePipe for DefaultValue<T> {
+    fn reset(&mut self) {}
+}
+
+/// Wrap a phase value into the range `[0.0, 1.0)`.
+fn wrap_phase(phase: f32) -> f32 {
+    let phase = phase.rem_euclid(1.0);
+    // `rem_euclid` may round up to exactly 1.0 for tiny negative values.
+    if phase < 1.0 {
+        phase
+    } else {
+        0.0
+    }
+}
+
+/// A wrapping phase accumulator.
+///
+/// This pipe is the common core of oscillators: Every time `next` is called, the current phase is returned and a fixed increment is added to it. The phase is always wrapped into the range `[0.0, 1.0)`, even if the increment is larger than 1.0. It also knows its initial phase and can therefore be reseted.
+///
+/// If the increment should be modulated, use the [`ModulatedPhasor`](struct.ModulatedPhasor.html) instead.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut phasor = Phasor::new(0.0, 0.25);
+/// assert_eq!(0.0, phasor.next(()));
+/// assert_eq!(0.25, phasor.next(()));
+/// assert_eq!(0.5, phasor.next(()));
+/// assert_eq!(0.75, phasor.next(()));
+/// assert_eq!(0.0, phasor.next(()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Phasor {
+    initial_phase: f32,
+    increment: f32,
+    phase: f32,
+}
+
+impl Phasor {
+    /// Create a new phasor with the given initial phase and increment per call.
+    pub fn new(initial_phase: f32, increment: f32) -> Self {
+        let initial_phase = wrap_phase(initial_phase);
+        Self {
+            initial_phase,
+            increment,
+            phase: initial_phase,
+        }
+    }
+
+    /// Return the increment per call.
+    pub fn increment(&self) -> f32 {
+        self.increment
+    }
+
+    /// Set the increment per call.
+    ///
+    /// The current phase is kept, so the phase stays continuous.
+    pub fn set_increment(&mut self, increment: f32) {
+        self.increment = increment;
+    }
+}
+
+impl Pipe for Phasor {
+    type InputItem = ();
+    type OutputItem = f32;
+
+    fn next(&mut self, _: ()) -> f32 {
+        let phase = self.phase;
+        self.phase = wrap_phase(self.phase + self.increment);
+        phase
+    }
+}
+
+impl ResetablePipe for Phasor {
+    fn reset(&mut self) {
+        self.phase = self.initial_phase;
+    }
+}
+
+/// A wrapping phase accumulator with a modulated increment.
+///
+/// This pipe behaves like the [`Phasor`](struct.Phasor.html), but the increment is the input item of every call. This way, the frequency of an oscillator can be modulated by another pipe.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut phasor = ModulatedPhasor::new(0.0);
+/// assert_eq!(0.0, phasor.next(0.25));
+/// assert_eq!(0.25, phasor.next(0.5));
+/// assert_eq!(0.75, phasor.next(0.5));
+/// assert_eq!(0.25, phasor.next(0.5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ModulatedPhasor {
+    initial_phase: f32,
+    phase: f32,
+}
+
+impl ModulatedPhasor {
+    /// Create a new modulated phasor with the given initial phase.
+    pub fn new(initial_phase: f32) -> Self {
+        let initial_phase = wrap_phase(initial_phase);
+        Self {
+            initial_phase,
+            phase: initial_phase,
+        }
+    }
+}
+
+impl Pipe for ModulatedPhasor {
+    type InputItem = f32;
+    type OutputItem = f32;
+
+    fn next(&mut self, increment: f32) -> f32 {
+        let phase = self.phase;
+        self.phase = wrap_phase(self.phase + increment);
+        phase
+    }
+}
+
+impl ResetablePipe for ModulatedPhasor {
+    fn reset(&mut self) {
+        self.phase = self.initial_phase;
+    }
+}
+
+/// A simple forwarding pipe.
+///
+/// This pipe takes an input item and immediately emits it again.
+///
+/// # Example
+///
+/// ```
+/// use iterpipes::*;
+///
+/// let mut ditto: Ditto<u8> = Ditto::default();
+///
+/// assert_eq!(ditto.next(42), 42);
+/// ```
+pub struct Ditto<T> {
+    item: PhantomData<T>,
+}
+
+impl<T> Default for Ditto<T> {
+    fn default() -> Self {
+        Self { item: PhantomData }
+    }
+}
+
+impl<T> Pipe for Ditto<T> {
+    type InputItem = T;
+    type OutputItem = T;
+
+    fn next(&mut self, item: T) -> T {
+        item
+    }
+}
+
+impl<T> ResetablePipe for Ditto<T> {
+    fn reset(&mut self) {}
+}
+
+#[test]
+fn bounded_counter_integer() {
+    let mut counter: BoundedCounter<u32> = BoundedCounter::new(2, 11, 3);
+    let items: Vec<u32> = (&mut counter).into_iter().collect();
+    assert_eq!(vec![2, 5, 8], items);
+    assert_eq!(None, counter.next(()));
+
+    counter.reset();
+    let items: Vec<u32> = (&mut counter).into_iter().collect();
+    assert_eq!(vec![2, 5, 8], items);
+}
+
+#[test]
+fn bounded_counter_float() {
+    let mut counter: BoundedCounter<f32> = BoundedCounter::new(0.0, 1.0, 0.25);
+    let items: Vec<f32> = (&mut counter).into_iter().collect();
+    assert_eq!(vec![0.0, 0.25, 0.5, 0.75], items);
+
+    counter.reset();
+    assert_eq!(Some(0.0), counter.next(()));
+    assert_eq!(Some(0.25), counter.next(()));
+}
+
+#[test]
+fn phasor_stability() {
+    let mut phasor = Phasor::new(0.5, 440.0 / 44100.0);
+    for _ in 0..5_000_000 {
+        let phase = phasor.next(());
+        assert!((0.0..1.0).contains(&phase));
+    }
+
+    let mut phasor = Phasor::new(-0.25, 3.3);
+    assert_eq!(0.75, phasor.next(()));
+    for _ in 0..1_000_000 {
+        let phase = phasor.next(());
+        assert!((0.0..1.0).contains(&phase));
+    }
+
+    phasor.reset();
+    assert_eq!(0.75, phasor.next(()));
+}
+
+#[test]
+fn modulated_phasor() {
+    let mut phasor = ModulatedPhasor::new(0.0);
+    assert_eq!(0.0, phasor.next(0.5));
+    assert_eq!(0.5, phasor.next(2.25));
+    assert_eq!(0.75, phasor.next(-0.5));
+    assert_eq!(0.25, phasor.next(-1e-9));
+    for i in 0..1_000_000 {
+        let phase = phasor.next((i % 7) as f32 * 0.37 - 1.0);
+        assert!((0.0..1.0).contains(&phase));
+    }
+
+    phasor.reset();
+    assert_eq!(0.0, phasor.next(0.5));
+}
+
+#[test]
+fn unwrap_decorators() {
+    let mut pipe = BoundedCounter::new(0, 2, 1).unwrap_or(7);
+    assert_eq!(
+        vec![0, 1, 7, 7],
+        (0..4).map(|_| pipe.next(())).collect::<Vec<i32>>()
+    );
+    pipe.reset();
+    assert_eq!(0, pipe.next(()));
+
+    let mut pipe = BoundedCounter::new(3, 4, 1).unwrap_or_default();
+    assert_eq!(
+        vec![3, 0, 0],
+        (0..3).map(|_| pipe.next(())).collect::<Vec<i32>>()
+    );
+    pipe.reset();
+    assert_eq!(3, pipe.next(()));
+
+    let mut pipe = BoundedCounter::new(3, 4, 1).unwrap_or_else(|| -1);
+    assert_eq!(
+        vec![3, -1, -1],
+        (0..3).map(|_| pipe.next(())).collect::<Vec<i32>>()
+    );
+    pipe.reset();
+    assert_eq!(3, pipe.next(()));
+}
+
+#[test]
+fn memoize() {
+    /// A pipe that squares its input and counts its invocations.
+    struct CountingSquare {
+        calls: usize,
+    }
+
+    impl Pipe for CountingSquare {
+        type InputItem = u32;
+        type OutputItem = u32;
+
+        fn next(&mut self, item: u32) -> u32 {
+            self.calls += 1;
+            item * item
+        }
+    }
+
+    impl ResetablePipe for CountingSquare {
+        fn reset(&mut self) {}
+    }
+
+    let mut inner = CountingSquare { calls: 0 };
+    let mut pipe = (&mut inner).memoize();
+    for item in &[1, 2, 1, 1, 3, 2] {
+        assert_eq!(item * item, pipe.next(*item));
+    }
+    assert_eq!(3, pipe.hits());
+    assert_eq!(3, pipe.misses());
+
+    pipe.reset_keep_cache();
+    pipe.next(1);
+    assert_eq!(4, pipe.hits());
+
+    pipe.reset();
+    assert_eq!(0, pipe.hits());
+    pipe.next(1);
+    assert_eq!(1, pipe.misses());
+
+    pipe.clear_cache();
+    pipe.next(1);
+    assert_eq!(2, pipe.misses());
+    assert_eq!(5, inner.calls);
+
+    let mut inner = CountingSquare { calls: 0 };
+    let mut pipe = (&mut inner).memoize_with_capacity(2);
+    for item in &[1, 2, 1, 3, 1, 3] {
+        assert_eq!(item * item, pipe.next(*item));
+    }
+    // Inserting 3 cleared the cache, so 1 had to be computed again.
+    assert_eq!(2, pipe.hits());
+    assert_eq!(4, inner.calls);
+}
+
+#[test]
+fn optional_skips_none() {
+    use crate::testing::MockPipe;
+
+    let mut mock: MockPipe<u8, u8> = MockPipe::new(vec![10, 20]);
+    let mut pipe = (&mut mock).optional();
+    assert_eq!(None, pipe.next(None));
+    assert_eq!(Some(10), pipe.next(Some(1)));
+    assert_eq!(None, pipe.next(None));
+    assert_eq!(Some(20), pipe.next(Some(2)));
+    pipe.reset();
+
+    assert_eq!(&[1, 2], mock.inputs());
+    assert_eq!(1, mock.resets());
+}
+
+#[test]
+fn gated_counter() {
+    let mut pipe = Counter::new(0u32, 1).gated().unwrap_or(42);
+    let gates = [true, false, true, false, false, true];
+    let output: Vec<u32> = gates.iter().map(|gate| pipe.next(((), *gate))).collect();
+    // The counter only advances on open samples.
+    assert_eq!(vec![0, 42, 1, 42, 42, 2], output);
+
+    pipe.reset();
+    assert_eq!(42, pipe.next(((), false)));
+    assert_eq!(0, pipe.next(((), true)));
+
+    let mut gate: Gate<u32> = Gate::default();
+    let output: Vec<u32> = gates
+        .iter()
+        .enumerate()
+        .map(|(i, gate_open)| gate.next((i as u32 + 1, *gate_open)))
+        .collect();
+    assert_eq!(vec![1, 0, 3, 0, 0, 6], output);
+}
+
+#[test]
+fn latch() {
+    let mut latch = Latch::new('a');
+    assert_eq!('b', latch.next(('b', true)));
+    for c in "cdefghijklmnop".chars() {
+        assert_eq!('b', latch.next((c, false)));
+    }
+    assert_eq!('q', latch.next(('q', true)));
+    assert_eq!('r', latch.next(('r', true)));
+    assert_eq!(&'r', latch.value());
+
+    latch.reset();
+    assert_eq!('a', latch.next(('s', false)));
+    assert_eq!('t', latch.next(('t', true)));
+    latch.reset();
+    assert_eq!(&'a', latch.value());
+    assert_eq!('a', latch.next(('u', false)));
+}
+
+#[test]
+fn pairwise() {
+    let mut pipe = Counter::new(0u8, 1).pairwise();
+    assert_eq!(None, pipe.next(()));
+    assert_eq!(Some((0, 1)), pipe.next(()));
+    assert_eq!(Some((1, 2)), pipe.next(()));
+
+    pipe.reset();
+    assert_eq!(None, pipe.next(()));
+    assert_eq!(Some((0, 1)), pipe.next(()));
+
+    let mut pipe = Counter::new(0u8, 1).pairwise_with(10);
+    assert_eq!((10, 0), pipe.next(()));
+    assert_eq!((0, 1), pipe.next(()));
+    assert_eq!((1, 2), pipe.next(()));
+
+    pipe.reset();
+    assert_eq!((10, 0), pipe.next(()));
+}
+
+#[test]
+fn state_machine() {
+    // Accumulates digits into a number, which is emitted on a separator.
+    let transition = |number: u32, c: char| match c.to_digit(10) {
+        Some(digit) => (number * 10 + digit, None),
+        None => (0, Some(number)),
+    };
+    let mut parser = StateMachine::new(0, transition);
+
+    let outputs: Vec<Option<u32>> = "12,3".chars().map(|c| parser.next(c)).collect();
+    assert_eq!(vec![None, None, Some(12), None], outputs);
+    assert_eq!(&3, parser.state());
+
+    // Resetting in the middle of a number discards it.
+    parser.reset();
+    assert_eq!(&0, parser.state());
+    let outputs: Vec<Option<u32>> = "45,".chars().map(|c| parser.next(c)).collect();
+    assert_eq!(vec![None, None, Some(45)], outputs);
+
+    crate::testing::assert_reset_law(StateMachine::new(0, transition), "1,22,333,".chars());
+}
+
+#[test]
+fn state_machine_panicking_transition() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut machine = StateMachine::new(0u8, |state: u8, input: u8| {
+        let state = state.checked_add(input).expect("overflow");
+        (state, state)
+    });
+    assert_eq!(200, machine.next(200));
+    assert!(catch_unwind(AssertUnwindSafe(|| machine.next(100))).is_err());
+
+    // The state was lost, so the machine refuses to continue until it's reset.
+    assert!(catch_unwind(AssertUnwindSafe(|| machine.next(1))).is_err());
+    assert!(catch_unwind(AssertUnwindSafe(|| *machine.state())).is_err());
+    machine.reset();
+    assert_eq!(1, machine.next(1));
+}
+
+#[test]
+fn unfold() {
+    let mut collatz = Unfold::new(6u32, |n: &mut u32| {
+        if *n == 0 {
+            return None;
+        }
+        let current = *n;
+        *n = match current {
+            1 => 0,
+            n if n.is_multiple_of(2) => n / 2,
+            n => 3 * n + 1,
+        };
+        Some(current)
+    });
+
+    let first: Vec<u32> = (&mut collatz).into_iter().collect();
+    assert_eq!(vec![6, 3, 10, 5, 16, 8, 4, 2, 1], first);
+    assert_eq!(None, collatz.next(()));
+
+    collatz.reset();
+    assert_eq!(&6, collatz.state());
+    let second: Vec<u32> = (&mut collatz).into_iter().collect();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn connector_parts() {
+    use crate::ScaleOffset;
+
+    let mut pipe = Counter::new(0u32, 1).connect(ScaleOffset::new(1, 0));
+    assert_eq!(0, pipe.next(()));
+    assert_eq!(1, pipe.next(()));
+
+    // Adjust the second half and observe the change.
+    pipe.second_mut().set_scale(100);
+    assert_eq!(100, pipe.second().scale());
+    assert_eq!(200, pipe.next(()));
+    pipe.first_mut().reset();
+    assert_eq!(0, pipe.next(()));
+
+    let (counter, scale) = pipe.parts_mut();
+    assert_eq!(1, counter.next(()));
+    assert_eq!(200, scale.next(2));
+
+    let (mut counter, _) = pipe.into_parts();
+    assert_eq!(2, counter.next(()));
+}
+
+#[test]
+fn bypass_with() {
+    /// An event that can't be cloned.
+    struct Event {
+        id: u32,
+        payload: Vec<u8>,
+    }
+
+    let mut pipe = Lazy::new(|payload: Vec<u8>| payload.len())
+        .bypass_with(|event: Event| (event.id, event.payload));
+    assert_eq!(
+        (7, 3),
+        pipe.next(Event {
+            id: 7,
+            payload: vec![1, 2, 3]
+        })
+    );
+
+    // Mutable references can be reborrowed for the inner pipe.
+    let mut pipe = LazyMut::new(|buffer: &mut [f32]| {
+        for sample in buffer.iter_mut() {
+            *sample *= 2.0;
+        }
+        buffer.len()
+    })
+    .bypass_with(|buffer: &mut [f32]| (buffer[0], buffer));
+    let mut buffer = [1.0, 2.0];
+    assert_eq!((1.0, 2), pipe.next(&mut buffer));
+    assert_eq!([2.0, 4.0], buffer);
+
+    let mut pipe = Counter::new(0u8, 1)
+        .connect(Ditto::default())
+        .bypass_with(|c: char| (c, ()));
+    assert_eq!(('a', 0), pipe.next('a'));
+    pipe.reset();
+    assert_eq!(('b', 0), pipe.next('b'));
+}
+
+#[test]
+fn peekable() {
+    use crate::testing::MockPipe;
+
+    let mut pipe = Counter::new(0u8, 1).peekable();
+    assert_eq!(&0, pipe.peek());
+    assert_eq!(&0, pipe.peek());
+    assert_eq!(0, pipe.next(()));
+    assert_eq!(1, pipe.next(()));
+    assert_eq!(&2, pipe.peek());
+    pipe.reset();
+    assert_eq!(&0, pipe.peek());
+    assert_eq!(0, pipe.next(()));
+    assert_eq!(1, pipe.next(()));
+    assert_eq!(&2, pipe.peek());
+    assert_eq!(vec![2], pipe.finish());
+    assert_eq!(3, pipe.next(()));
+
+    // Peeking the end of the stream doesn't poll the mock again, which would make it panic.
+    let mock: MockPipe<(), Option<u8>> = MockPipe::new(vec![Some(1), None]);
+    let mut pipe = mock.peekable();
+    assert_eq!(&Some(1), pipe.peek());
+    assert_eq!(Some(1), pipe.next(()));
+    assert_eq!(&None, pipe.peek());
+    assert_eq!(&None, pipe.peek());
+    assert_eq!(None, pipe.next(()));
+}
+
+#[test]
+fn convert() {
+    /// A newtype for sample positions.
+    #[derive(Debug, PartialEq)]
+    struct Position(u32);
+
+    impl From<Position> for u64 {
+        fn from(position: Position) -> u64 {
+            position.0 as u64
+        }
+    }
+
+    impl From<u16> for Position {
+        fn from(position: u16) -> Position {
+            Position(position as u32)
+        }
+    }
+
+    let mut pipe = LazyMut::new(|i: Position| Position(i.0 * 2))
+        .convert_input::<u16>()
+        .convert::<u64>();
+    assert_eq!(8u64, pipe.next(4u16));
+
+    let mut pipe = Counter::new(0u8, 50).convert::<u16>().compose()
+        >> Lazy::new(|i: u16| i * 4)
+        >> Ditto::<u32>::default().convert_input::<u16>();
+    assert_eq!(0, pipe.next(()));
+    assert_eq!(200, pipe.next(()));
+    assert_eq!(400, pipe.next(()));
+    pipe.reset();
+    assert_eq!(0, pipe.next(()));
 }